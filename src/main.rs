@@ -10,6 +10,12 @@ mod sort;        // 算法集合：各种排序算法
 mod interface_practice; // 高级特性：trait、生命周期、异步等
 
 fn main() {
+    // 命令行模式：synax sort [选项] [文件]
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("sort") {
+        std::process::exit(sort::cli::main(&args[1..]));
+    }
+
     // println!("🦀 欢迎使用 Rust 语法练习项目！");
     // println!("现在包含 20 个核心特性示例，取消注释来运行：");
     // println!();
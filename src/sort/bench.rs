@@ -0,0 +1,296 @@
+// 测试特性：基准测试框架、输入分布生成、计时(Instant)、表格与JSON输出
+// 语法要点：enum + 关联常量ALL、std::time::Instant、Duration、serde_json::json!
+// 功能：在多种输入分布和规模下运行注册表中的每个排序算法，
+//       记录耗时和比较/交换次数，输出对比表格或 JSON
+
+use std::time::{Duration, Instant};
+
+use serde_json::{json, Value};
+
+use super::instrument::{measure, SortStats};
+use super::rng::Rng;
+use super::sorter::{BucketSort, Registry};
+
+// 输入数据的分布
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Distribution {
+    Random,
+    Sorted,
+    Reversed,
+    // 先升后降：0, 1, ..., n/2, ..., 1, 0
+    OrganPipe,
+    // 只有少量不同取值
+    FewUnique,
+    // 多段重复的升序锯齿
+    Sawtooth,
+    // 有序数组中随机交换了约 1% 的元素
+    NearlySorted,
+}
+
+impl Distribution {
+    pub const ALL: [Distribution; 7] = [
+        Distribution::Random,
+        Distribution::Sorted,
+        Distribution::Reversed,
+        Distribution::OrganPipe,
+        Distribution::FewUnique,
+        Distribution::Sawtooth,
+        Distribution::NearlySorted,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Distribution::Random => "random",
+            Distribution::Sorted => "sorted",
+            Distribution::Reversed => "reversed",
+            Distribution::OrganPipe => "organ-pipe",
+            Distribution::FewUnique => "few-unique",
+            Distribution::Sawtooth => "sawtooth",
+            Distribution::NearlySorted => "nearly-sorted",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Distribution> {
+        Distribution::ALL.iter().copied().find(|d| d.name() == name)
+    }
+
+    pub fn generate(&self, n: usize, rng: &mut Rng) -> Vec<u32> {
+        match self {
+            Distribution::Random => (0..n).map(|_| rng.next_u32()).collect(),
+            Distribution::Sorted => (0..n as u32).collect(),
+            Distribution::Reversed => (0..n as u32).rev().collect(),
+            Distribution::OrganPipe => (0..n).map(|i| i.min(n - 1 - i) as u32).collect(),
+            Distribution::FewUnique => (0..n).map(|_| rng.below(8) as u32).collect(),
+            Distribution::Sawtooth => {
+                let tooth = (n / 8).max(1);
+                (0..n).map(|i| (i % tooth) as u32).collect()
+            }
+            Distribution::NearlySorted => {
+                let mut data: Vec<u32> = (0..n as u32).collect();
+                if n == 0 {
+                    return data;
+                }
+                for _ in 0..n / 100 + 1 {
+                    let i = rng.below(n as u64) as usize;
+                    let j = rng.below(n as u64) as usize;
+                    data.swap(i, j);
+                }
+                data
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BenchConfig {
+    pub sizes: Vec<usize>,
+    pub distributions: Vec<Distribution>,
+    pub seed: u64,
+    // 平均复杂度为 O(n²) 的算法只在不超过该规模的输入上运行
+    pub quadratic_limit: usize,
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        BenchConfig {
+            sizes: vec![100, 1_000, 10_000],
+            distributions: Distribution::ALL.to_vec(),
+            seed: 0x5eed,
+            quadratic_limit: 10_000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchResult {
+    pub algorithm: &'static str,
+    pub distribution: Distribution,
+    pub size: usize,
+    pub elapsed: Duration,
+    pub stats: SortStats,
+}
+
+// 对每种分布和规模生成一份输入，所有算法排序同一份输入的拷贝。
+// 输入先按 u32 生成再转换成 T，同一个种子在任何元素类型上得到的都是同一组数据。
+// 计时和计数分两次运行，避免观察者的开销影响耗时
+pub fn run<T>(registry: &Registry<T>, config: &BenchConfig) -> Vec<BenchResult>
+where
+    T: From<u32> + PartialOrd + Clone,
+{
+    let mut rng = Rng::new(config.seed);
+    let mut results = Vec::new();
+    for &distribution in &config.distributions {
+        for &size in &config.sizes {
+            let input: Vec<T> = distribution.generate(size, &mut rng).into_iter().map(T::from).collect();
+            for sorter in registry.iter() {
+                if sorter.is_quadratic() && size > config.quadratic_limit {
+                    continue;
+                }
+
+                let mut data = input.clone();
+                let start = Instant::now();
+                sorter.sort(&mut data);
+                let elapsed = start.elapsed();
+                debug_assert!(data.windows(2).all(|w| w[0] <= w[1]), "{}", sorter.name());
+
+                let stats = measure(sorter, &mut input.clone());
+                results.push(BenchResult {
+                    algorithm: sorter.name(),
+                    distribution,
+                    size,
+                    elapsed,
+                    stats,
+                });
+            }
+        }
+    }
+    results
+}
+
+// 运行 sort 模块中的全部算法。u32 注册表包含除 bucket 以外的所有算法，
+// bucket 只能排序 f64，因此在转换成 f64 的同一组输入上单独运行
+pub fn run_all(config: &BenchConfig) -> Vec<BenchResult> {
+    let mut results = run(&Registry::<u32>::all(), config);
+    let mut floats = Registry::<f64>::new();
+    floats.register(BucketSort);
+    results.extend(run(&floats, config));
+    results
+}
+
+pub fn format_table(results: &[BenchResult]) -> String {
+    let mut table = format!(
+        "{:<10} {:<14} {:>8} {:>12} {:>12} {:>12} {:>12}\n",
+        "算法", "分布", "规模", "耗时(µs)", "比较", "交换", "移动"
+    );
+    for r in results {
+        table.push_str(&format!(
+            "{:<10} {:<14} {:>8} {:>12} {:>12} {:>12} {:>12}\n",
+            r.algorithm,
+            r.distribution.name(),
+            r.size,
+            r.elapsed.as_micros(),
+            r.stats.comparisons,
+            r.stats.swaps,
+            r.stats.moves
+        ));
+    }
+    table
+}
+
+pub fn to_json(results: &[BenchResult]) -> Value {
+    let rows: Vec<Value> = results
+        .iter()
+        .map(|r| {
+            json!({
+                "algorithm": r.algorithm,
+                "distribution": r.distribution.name(),
+                "size": r.size,
+                "elapsed_ns": r.elapsed.as_nanos() as u64,
+                "comparisons": r.stats.comparisons,
+                "swaps": r.stats.swaps,
+                "moves": r.stats.moves,
+                "allocations": r.stats.allocations,
+            })
+        })
+        .collect();
+    Value::Array(rows)
+}
+
+// 演示函数：使用默认配置运行基准测试并打印表格
+pub fn demo_benchmark() {
+    println!("=== 排序算法基准测试 ===");
+    let results = run_all(&BenchConfig::default());
+    print!("{}", format_table(&results));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sort::sorter::ALGORITHMS;
+
+    fn small_config() -> BenchConfig {
+        BenchConfig {
+            sizes: vec![0, 1, 50, 200],
+            quadratic_limit: 50,
+            ..BenchConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_distributions() {
+        let mut rng = Rng::new(1);
+        let n = 1000;
+        let sorted = Distribution::Sorted.generate(n, &mut rng);
+        assert!(sorted.windows(2).all(|w| w[0] <= w[1]));
+        let reversed = Distribution::Reversed.generate(n, &mut rng);
+        assert!(reversed.windows(2).all(|w| w[0] >= w[1]));
+        let pipe = Distribution::OrganPipe.generate(9, &mut rng);
+        assert_eq!(pipe, vec![0, 1, 2, 3, 4, 3, 2, 1, 0]);
+        let few = Distribution::FewUnique.generate(n, &mut rng);
+        assert!(few.iter().all(|&x| x < 8));
+        let saw = Distribution::Sawtooth.generate(16, &mut rng);
+        assert_eq!(saw, vec![0, 1, 0, 1, 0, 1, 0, 1, 0, 1, 0, 1, 0, 1, 0, 1]);
+        let nearly = Distribution::NearlySorted.generate(n, &mut rng);
+        let out_of_place = nearly.iter().enumerate().filter(|&(i, &x)| i as u32 != x).count();
+        assert!(out_of_place > 0 && out_of_place <= 2 * (n / 100 + 1));
+        for d in Distribution::ALL {
+            assert_eq!(d.generate(n, &mut rng).len(), n);
+            assert_eq!(Distribution::from_name(d.name()), Some(d));
+        }
+    }
+
+    #[test]
+    fn test_run_covers_every_cell() {
+        let registry = Registry::<u32>::all();
+        let config = small_config();
+        let results = run(&registry, &config);
+        let quadratic = registry.iter().filter(|s| s.is_quadratic()).count();
+        // 规模 200 超过 quadratic_limit，O(n²) 算法被跳过
+        let per_distribution = 3 * registry.len() + (registry.len() - quadratic);
+        assert_eq!(results.len(), Distribution::ALL.len() * per_distribution);
+        assert!(results.iter().all(|r| r.size <= 50 || !r.algorithm.contains("bubble")));
+    }
+
+    #[test]
+    fn test_run_all_covers_every_algorithm() {
+        let config = BenchConfig {
+            sizes: vec![30],
+            distributions: vec![Distribution::Random],
+            ..BenchConfig::default()
+        };
+        let results = run_all(&config);
+        let mut names: Vec<_> = results.iter().map(|r| r.algorithm).collect();
+        let mut expected: Vec<_> = ALGORITHMS.iter().map(|info| info.name).collect();
+        names.sort_unstable();
+        expected.sort_unstable();
+        assert_eq!(names, expected);
+    }
+
+    #[test]
+    fn test_same_seed_same_counts() {
+        let registry = Registry::<u32>::all();
+        let first: Vec<_> = run(&registry, &small_config()).into_iter().map(|r| r.stats).collect();
+        let second: Vec<_> = run(&registry, &small_config()).into_iter().map(|r| r.stats).collect();
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn test_table_and_json() {
+        let config = BenchConfig {
+            sizes: vec![20],
+            distributions: vec![Distribution::Reversed],
+            ..BenchConfig::default()
+        };
+        let results = run(&Registry::<u32>::all(), &config);
+        let table = format_table(&results);
+        assert_eq!(table.lines().count(), results.len() + 1);
+        assert!(table.contains("reversed"));
+
+        let json = to_json(&results);
+        assert_eq!(json.as_array().unwrap().len(), results.len());
+        let bubble = &json[0];
+        assert_eq!(bubble["algorithm"], "bubble");
+        assert_eq!(bubble["comparisons"], 190);
+        assert_eq!(bubble["swaps"], 190);
+    }
+}
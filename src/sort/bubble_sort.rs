@@ -1,17 +1,54 @@
 // 测试特性：冒泡排序算法、泛型(Generics)、Trait约束、单元测试
 // 语法要点：<T: PartialOrd>、切片操作、swap方法、#[cfg(test)]、assert_eq!
 // 功能：实现泛型冒泡排序算法，支持任何实现PartialOrd的类型
+use std::cmp::Ordering;
+
+use super::instrument::{NoopObserver, SortObserver};
+
 pub fn bubble_sort<T: PartialOrd>(arr: &mut [T]) {
+    bubble_sort_by(arr, |a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+}
+
+// 降序排序
+pub fn bubble_sort_desc<T: PartialOrd>(arr: &mut [T]) {
+    bubble_sort_by(arr, |a, b| b.partial_cmp(a).unwrap_or(Ordering::Equal));
+}
+
+// 使用自定义比较函数排序
+pub fn bubble_sort_by<T, F>(arr: &mut [T], compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    bubble_sort_observed(arr, compare, &mut NoopObserver);
+}
+
+// 排序的同时把每次比较和交换通知给观察者
+pub fn bubble_sort_observed<T, F, O>(arr: &mut [T], mut compare: F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver + ?Sized,
+{
     let n = arr.len();
     for i in 0..n {
         for j in 0..n - 1 - i {
-            if arr[j] > arr[j + 1] {
+            observer.on_compare(j, j + 1);
+            if compare(&arr[j], &arr[j + 1]) == Ordering::Greater {
+                observer.on_swap(j, j + 1);
                 arr.swap(j, j + 1);
             }
         }
     }
 }
 
+// 按提取出的键排序，例如按结构体的某个字段
+pub fn bubble_sort_by_key<T, K, F>(arr: &mut [T], mut key: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    bubble_sort_by(arr, |a, b| key(a).cmp(&key(b)));
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -36,4 +73,4 @@ mod tests {
         bubble_sort(&mut arr);
         assert_eq!(arr, vec![1]);
     }
-}
\ No newline at end of file
+}
@@ -1,34 +1,118 @@
+// 测试特性：桶排序、浮点数全序(total_cmp)、NaN与±0处理、键提取闭包
+// 语法要点：f64::total_cmp、is_finite、is_sign_negative、FnMut(&T) -> f64
+// 功能：按观测到的最小/最大值把元素均匀分配到可配置数量的桶中，
+//       使用与 f64::total_cmp 一致的全序（-NaN < -∞ < … < -0 < +0 < … < +∞ < +NaN），
+//       并支持把任意记录映射为 f64 键后稳定排序
+use std::cmp::Ordering;
+
+use super::instrument::{NoopObserver, SortObserver};
+use super::radix_sort::apply_permutation;
+
 pub fn bucket_sort(arr: &mut [f64]) {
-    if arr.is_empty() {
+    let bucket_count = arr.len();
+    bucket_sort_with(arr, bucket_count);
+}
+
+pub fn bucket_sort_with(arr: &mut [f64], bucket_count: usize) {
+    bucket_sort_by_key_with(arr, bucket_count, |&x| x);
+}
+
+pub fn bucket_sort_observed<O>(arr: &mut [f64], bucket_count: usize, observer: &mut O)
+where
+    O: SortObserver + ?Sized,
+{
+    bucket_sort_impl(arr, bucket_count, |&x| x, observer);
+}
+
+pub fn bucket_sort_by_key<T, F>(arr: &mut [T], key: F)
+where
+    F: FnMut(&T) -> f64,
+{
+    let bucket_count = arr.len();
+    bucket_sort_by_key_with(arr, bucket_count, key);
+}
+
+// 按 f64 键稳定排序，记录本身不需要 Clone
+pub fn bucket_sort_by_key_with<T, F>(arr: &mut [T], bucket_count: usize, key: F)
+where
+    F: FnMut(&T) -> f64,
+{
+    bucket_sort_impl(arr, bucket_count, key, &mut NoopObserver);
+}
+
+fn bucket_sort_impl<T, F, O>(arr: &mut [T], bucket_count: usize, key: F, observer: &mut O)
+where
+    F: FnMut(&T) -> f64,
+    O: SortObserver + ?Sized,
+{
+    if arr.len() <= 1 {
         return;
     }
+    observer.on_alloc(arr.len());
+    let keys: Vec<f64> = arr.iter().map(key).collect();
+    let mut order = bucket_order(&keys, bucket_count.max(1), observer);
+    apply_permutation(arr, &mut order, observer);
+}
+
+// 返回排序后的下标序列：order[i] 为排在第 i 位的键的原下标
+fn bucket_order<O>(keys: &[f64], bucket_count: usize, observer: &mut O) -> Vec<usize>
+where
+    O: SortObserver + ?Sized,
+{
+    let mut finite = keys.iter().copied().filter(|x| x.is_finite());
+    let first = finite.next().unwrap_or(0.0);
+    let (min, max) = finite.fold((first, first), |(lo, hi), x| (lo.min(x), hi.max(x)));
+    // 先各自除以 2 再相减，避免 max - min 溢出为无穷大
+    let half_range = max / 2.0 - min / 2.0;
+    let last = bucket_count - 1;
     
-    let n = arr.len();
-    let mut buckets: Vec<Vec<f64>> = vec![vec![]; n];
-    
-    for &item in arr.iter() {
-        let bucket_idx = (item * n as f64) as usize;
-        let bucket_idx = if bucket_idx >= n { n - 1 } else { bucket_idx };
-        buckets[bucket_idx].push(item);
-    }
+    let bucket_of = |x: f64| -> usize {
+        if x.is_nan() || x.is_infinite() {
+            // -NaN、-∞ 排在最前，+∞、+NaN 排在最后
+            return if x.is_sign_negative() { 0 } else { last };
+        }
+        if half_range == 0.0 {
+            return 0;
+        }
+        let position = (x / 2.0 - min / 2.0) / half_range;
+        ((position * bucket_count as f64) as usize).min(last)
+    };
     
-    for bucket in buckets.iter_mut() {
-        bucket.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    observer.on_alloc(keys.len());
+    let mut buckets: Vec<Vec<usize>> = vec![Vec::new(); bucket_count];
+    for (index, &x) in keys.iter().enumerate() {
+        buckets[bucket_of(x)].push(index);
     }
     
-    let mut idx = 0;
-    for bucket in buckets {
-        for item in bucket {
-            arr[idx] = item;
-            idx += 1;
+    // 桶内用插入排序（稳定），桶之间的顺序已经由分桶保证；
+    // 桶中存放的是原下标，因此向观察者报告的也是元素在原切片中的位置
+    let mut order = Vec::with_capacity(keys.len());
+    for mut bucket in buckets {
+        for i in 1..bucket.len() {
+            let mut j = i;
+            while j > 0 {
+                let (a, b) = (bucket[j - 1], bucket[j]);
+                observer.on_compare(a, b);
+                if keys[a].total_cmp(&keys[b]) != Ordering::Greater {
+                    break;
+                }
+                bucket.swap(j - 1, j);
+                j -= 1;
+            }
         }
+        order.extend(bucket);
     }
+    order
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn bits(arr: &[f64]) -> Vec<u64> {
+        arr.iter().map(|x| x.to_bits()).collect()
+    }
+
     #[test]
     fn test_bucket_sort() {
         let mut arr = vec![0.897, 0.565, 0.656, 0.1234, 0.665, 0.3434];
@@ -40,7 +124,7 @@ mod tests {
     fn test_empty_array() {
         let mut arr: Vec<f64> = vec![];
         bucket_sort(&mut arr);
-        assert_eq!(arr, Vec::<i32>::new());
+        assert_eq!(arr, Vec::<f64>::new());
     }
 
     #[test]
@@ -49,4 +133,47 @@ mod tests {
         bucket_sort(&mut arr);
         assert_eq!(arr, vec![0.5]);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_arbitrary_ranges() {
+        let mut arr = vec![-250.5, 1e9, 3.0, -1e9, 0.0, 42.0, -3.0];
+        bucket_sort(&mut arr);
+        assert_eq!(arr, vec![-1e9, -250.5, -3.0, 0.0, 3.0, 42.0, 1e9]);
+
+        // max - min 超出 f64 范围时也不能出错
+        let mut extremes = vec![f64::MAX, 1.0, f64::MIN, -1.0];
+        bucket_sort_with(&mut extremes, 3);
+        assert_eq!(extremes, vec![f64::MIN, -1.0, 1.0, f64::MAX]);
+    }
+
+    #[test]
+    fn test_total_order_for_nan_and_signed_zero() {
+        let mut arr = vec![f64::NAN, 1.0, 0.0, f64::INFINITY, -0.0, -f64::NAN, f64::NEG_INFINITY, -1.0];
+        let mut expected = arr.clone();
+        expected.sort_by(|a, b| a.total_cmp(b));
+        bucket_sort(&mut arr);
+        assert_eq!(bits(&arr), bits(&expected));
+        assert!(arr[0].is_nan() && arr[0].is_sign_negative());
+        assert!(arr[7].is_nan() && arr[7].is_sign_positive());
+    }
+
+    #[test]
+    fn test_bucket_counts() {
+        let input: Vec<f64> = (0..200).map(|i| ((i * 7919) % 200) as f64 - 100.0).collect();
+        let mut expected = input.clone();
+        expected.sort_by(|a, b| a.total_cmp(b));
+        for bucket_count in [0, 1, 2, 10, 1000] {
+            let mut arr = input.clone();
+            bucket_sort_with(&mut arr, bucket_count);
+            assert_eq!(arr, expected, "bucket_count = {}", bucket_count);
+        }
+    }
+
+    #[test]
+    fn test_bucket_sort_by_key_is_stable() {
+        let mut cities = vec![("oslo", 2.5), ("cairo", 35.1), ("lima", 18.0), ("bergen", 2.5), ("quito", 18.0)];
+        bucket_sort_by_key(&mut cities, |&(_, temp)| temp);
+        let names: Vec<_> = cities.iter().map(|c| c.0).collect();
+        assert_eq!(names, vec!["oslo", "bergen", "lima", "quito", "cairo"]);
+    }
+}
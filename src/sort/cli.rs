@@ -0,0 +1,377 @@
+// 测试特性：命令行参数解析、BufRead/Write 泛型 I/O、按字段提取排序键
+// 语法要点：迭代器逐个消费参数、enum 错误类型 + Display、Box<dyn BufRead> 统一文件与标准输入
+// 功能：实现 `synax sort` 子命令：从文件或标准输入读取行，按指定字段、数值或文本、
+//       升序或降序，用注册表中选定的算法排序后写到标准输出或文件
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::PathBuf;
+
+use super::sorter::Registry;
+
+pub const USAGE: &str = "\
+用法: synax sort [选项] [文件]
+
+从文件读取行并排序后输出；未指定文件或文件为 - 时读取标准输入
+
+选项:
+  -a, --algo <名称>        排序算法 (默认 merge): bubble, selection, insertion, merge, tim, quick, heap, shell
+  -n, --numeric            按数值比较；无法解析为数字的键排在最前面
+  -r, --reverse            降序输出
+  -k, --key <N>            按第 N 个字段排序 (从 1 开始)，默认整行
+  -t, --delimiter <字符>   字段分隔符，默认按连续空白分隔
+  -o, --output <文件>      写入文件而不是标准输出
+  -h, --help               显示帮助
+
+无论选择哪种算法，键相等的行都保持输入顺序";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SortOptions {
+    pub algorithm: String,
+    pub numeric: bool,
+    pub reverse: bool,
+    // 从 1 开始的字段编号
+    pub key: Option<usize>,
+    pub delimiter: Option<char>,
+    pub input: Option<PathBuf>,
+    pub output: Option<PathBuf>,
+    pub help: bool,
+}
+
+impl Default for SortOptions {
+    fn default() -> Self {
+        SortOptions {
+            algorithm: "merge".to_string(),
+            numeric: false,
+            reverse: false,
+            key: None,
+            delimiter: None,
+            input: None,
+            output: None,
+            help: false,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum CliError {
+    UnknownOption(String),
+    MissingValue(String),
+    InvalidValue { option: String, value: String },
+    UnexpectedArgument(String),
+    Algorithm(String),
+    Io(io::Error),
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::UnknownOption(option) => write!(f, "未知选项: {}", option),
+            CliError::MissingValue(option) => write!(f, "选项 {} 缺少参数", option),
+            CliError::InvalidValue { option, value } => write!(f, "选项 {} 的参数无效: {}", option, value),
+            CliError::UnexpectedArgument(arg) => write!(f, "多余的参数: {}", arg),
+            CliError::Algorithm(message) => write!(f, "{}", message),
+            CliError::Io(err) => write!(f, "I/O 错误: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for CliError {}
+
+impl From<io::Error> for CliError {
+    fn from(err: io::Error) -> Self {
+        CliError::Io(err)
+    }
+}
+
+// 解析 sort 之后的参数，支持 `--key 2` 与 `--key=2` 两种写法
+pub fn parse_args<I, S>(args: I) -> Result<SortOptions, CliError>
+where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+{
+    let mut options = SortOptions::default();
+    let mut args = args.into_iter().map(Into::into);
+    while let Some(arg) = args.next() {
+        let (flag, inline) = match arg.split_once('=') {
+            Some((flag, value)) if arg.starts_with("--") => (flag.to_string(), Some(value.to_string())),
+            _ => (arg.clone(), None),
+        };
+        let mut value = |name: &str| {
+            inline
+                .clone()
+                .or_else(|| args.next())
+                .ok_or_else(|| CliError::MissingValue(name.to_string()))
+        };
+        match flag.as_str() {
+            "-a" | "--algo" => options.algorithm = value(&flag)?,
+            "-n" | "--numeric" => options.numeric = true,
+            "-r" | "--reverse" => options.reverse = true,
+            "-h" | "--help" => options.help = true,
+            "-k" | "--key" => {
+                let raw = value(&flag)?;
+                match raw.parse::<usize>() {
+                    Ok(key) if key >= 1 => options.key = Some(key),
+                    _ => return Err(CliError::InvalidValue { option: flag, value: raw }),
+                }
+            }
+            "-t" | "--delimiter" => {
+                let raw = value(&flag)?;
+                let mut chars = raw.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => options.delimiter = Some(c),
+                    _ => return Err(CliError::InvalidValue { option: flag, value: raw }),
+                }
+            }
+            "-o" | "--output" => options.output = Some(PathBuf::from(value(&flag)?)),
+            "-" => set_input(&mut options, arg)?,
+            _ if flag.starts_with('-') => return Err(CliError::UnknownOption(arg)),
+            _ => set_input(&mut options, arg)?,
+        }
+    }
+    Ok(options)
+}
+
+fn set_input(options: &mut SortOptions, arg: String) -> Result<(), CliError> {
+    if options.input.is_some() {
+        return Err(CliError::UnexpectedArgument(arg));
+    }
+    options.input = Some(PathBuf::from(arg));
+    Ok(())
+}
+
+// 排序键：数值模式下无法解析的键归为 Text，排在所有数字之前
+#[derive(Debug, Clone, PartialEq)]
+enum SortKey {
+    Text(String),
+    Number(f64),
+}
+
+impl SortKey {
+    fn cmp(&self, other: &SortKey) -> Ordering {
+        match (self, other) {
+            (SortKey::Number(a), SortKey::Number(b)) => a.total_cmp(b),
+            (SortKey::Text(a), SortKey::Text(b)) => a.cmp(b),
+            (SortKey::Text(_), SortKey::Number(_)) => Ordering::Less,
+            (SortKey::Number(_), SortKey::Text(_)) => Ordering::Greater,
+        }
+    }
+}
+
+// 一行输入及其排序键。排序器只接受 PartialOrd 类型，因此把方向也存进记录里；
+// 键相等时按输入行号比较，不稳定的算法也能保持相等行的输入顺序
+#[derive(Debug, Clone)]
+struct Line {
+    key: SortKey,
+    reverse: bool,
+    index: usize,
+    text: String,
+}
+
+impl PartialEq for Line {
+    fn eq(&self, other: &Self) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+}
+
+impl PartialOrd for Line {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        let order = self.key.cmp(&other.key);
+        let order = if self.reverse { order.reverse() } else { order };
+        Some(order.then(self.index.cmp(&other.index)))
+    }
+}
+
+fn extract_key(line: &str, options: &SortOptions) -> SortKey {
+    let field = match (options.key, options.delimiter) {
+        (None, _) => line,
+        (Some(n), Some(delimiter)) => line.split(delimiter).nth(n - 1).unwrap_or(""),
+        (Some(n), None) => line.split_whitespace().nth(n - 1).unwrap_or(""),
+    };
+    if options.numeric {
+        if let Ok(number) = field.trim().parse::<f64>() {
+            if !number.is_nan() {
+                return SortKey::Number(number);
+            }
+        }
+    }
+    SortKey::Text(field.to_string())
+}
+
+// 读取全部行、排序并写出，每行以换行符结尾
+pub fn run<R: BufRead, W: Write>(options: &SortOptions, input: R, output: W) -> Result<(), CliError> {
+    let mut lines = Vec::new();
+    for (index, line) in input.lines().enumerate() {
+        let text = line?;
+        lines.push(Line {
+            key: extract_key(&text, options),
+            reverse: options.reverse,
+            index,
+            text,
+        });
+    }
+
+    Registry::<Line>::comparison()
+        .sort(&options.algorithm, &mut lines)
+        .map_err(CliError::Algorithm)?;
+
+    let mut output = BufWriter::new(output);
+    for line in &lines {
+        writeln!(output, "{}", line.text)?;
+    }
+    output.flush()?;
+    Ok(())
+}
+
+// 命令行入口，返回进程退出码
+pub fn main<S: AsRef<str>>(args: &[S]) -> i32 {
+    let options = match parse_args(args.iter().map(|arg| arg.as_ref().to_string())) {
+        Ok(options) => options,
+        Err(err) => {
+            eprintln!("synax sort: {}\n\n{}", err, USAGE);
+            return 2;
+        }
+    };
+    if options.help {
+        println!("{}", USAGE);
+        return 0;
+    }
+    match execute(&options) {
+        Ok(()) => 0,
+        Err(err) => {
+            eprintln!("synax sort: {}", err);
+            1
+        }
+    }
+}
+
+fn execute(options: &SortOptions) -> Result<(), CliError> {
+    let input: Box<dyn BufRead> = match &options.input {
+        Some(path) if path.as_os_str() != "-" => Box::new(BufReader::new(File::open(path)?)),
+        _ => Box::new(io::stdin().lock()),
+    };
+    match &options.output {
+        Some(path) => run(options, input, File::create(path)?),
+        None => run(options, input, io::stdout().lock()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sort_text(args: &[&str], input: &str) -> Result<String, CliError> {
+        let options = parse_args(args.iter().copied())?;
+        let mut output = Vec::new();
+        run(&options, input.as_bytes(), &mut output)?;
+        Ok(String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let options = parse_args(["--algo", "heap", "--numeric", "--reverse", "--key", "2", "--delimiter", ",", "input.csv"]).unwrap();
+        assert_eq!(
+            options,
+            SortOptions {
+                algorithm: "heap".to_string(),
+                numeric: true,
+                reverse: true,
+                key: Some(2),
+                delimiter: Some(','),
+                input: Some(PathBuf::from("input.csv")),
+                ..SortOptions::default()
+            }
+        );
+
+        let short = parse_args(["-nr", "-k=1"]);
+        assert!(matches!(short, Err(CliError::UnknownOption(_))));
+        let inline = parse_args(["--key=3", "-t", "\t", "-"]).unwrap();
+        assert_eq!((inline.key, inline.delimiter), (Some(3), Some('\t')));
+        assert_eq!(inline.input, Some(PathBuf::from("-")));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(matches!(parse_args(["--key"]), Err(CliError::MissingValue(_))));
+        assert!(matches!(parse_args(["--key", "0"]), Err(CliError::InvalidValue { .. })));
+        assert!(matches!(parse_args(["-t", "ab"]), Err(CliError::InvalidValue { .. })));
+        assert!(matches!(parse_args(["--bogus"]), Err(CliError::UnknownOption(_))));
+        assert!(matches!(parse_args(["a.txt", "b.txt"]), Err(CliError::UnexpectedArgument(_))));
+    }
+
+    #[test]
+    fn test_sort_whole_lines() {
+        assert_eq!(sort_text(&[], "pear\nfig\nbanana\n").unwrap(), "banana\nfig\npear\n");
+        assert_eq!(sort_text(&["-r"], "b\na\nc").unwrap(), "c\nb\na\n");
+        assert_eq!(sort_text(&[], "").unwrap(), "");
+    }
+
+    #[test]
+    fn test_numeric_csv_key() {
+        let csv = "alice,30\nbob,4\ncarol,100\ndave,n/a\n";
+        let text = sort_text(&["--algo", "heap", "--numeric", "--key", "2", "--delimiter", ","], csv).unwrap();
+        assert_eq!(text, "dave,n/a\nbob,4\nalice,30\ncarol,100\n");
+        // 不按数值比较时 "100" < "30" < "4"
+        let text = sort_text(&["-k", "2", "-t", ","], csv).unwrap();
+        assert_eq!(text, "carol,100\nalice,30\nbob,4\ndave,n/a\n");
+    }
+
+    #[test]
+    fn test_reverse_is_stable_with_every_algorithm() {
+        let input = "x 2\ny 1\nz 2\nw 3\n";
+        for algo in Registry::<String>::comparison().names() {
+            let text = sort_text(&["-a", algo, "-n", "-r", "-k", "2"], input).unwrap();
+            let first_twos: Vec<_> = text.lines().filter(|l| l.ends_with('2')).collect();
+            assert!(text.starts_with("w 3\n") && text.ends_with("y 1\n"), "{}", algo);
+            // 行号作为最后的比较依据，不稳定的算法同样保持 x 在 z 之前
+            assert_eq!(first_twos, vec!["x 2", "z 2"], "{}", algo);
+        }
+
+        // 大量相等键：每个键内部的行号必须保持递增
+        let input: String = (0..300).map(|i| format!("{} {}\n", i, i * 7 % 5)).collect();
+        for algo in Registry::<String>::comparison().names() {
+            for reverse in [false, true] {
+                let mut args = vec!["-a", algo, "-n", "-k", "2"];
+                if reverse {
+                    args.push("-r");
+                }
+                let text = sort_text(&args, &input).unwrap();
+                let rows: Vec<(u32, u32)> = text
+                    .lines()
+                    .map(|l| {
+                        let (index, key) = l.split_once(' ').unwrap();
+                        (key.parse().unwrap(), index.parse().unwrap())
+                    })
+                    .collect();
+                let in_order = rows.windows(2).all(|w| {
+                    let keys = if reverse { w[1].0.cmp(&w[0].0) } else { w[0].0.cmp(&w[1].0) };
+                    keys.then(w[0].1.cmp(&w[1].1)) == Ordering::Less
+                });
+                assert!(in_order, "{} reverse={}", algo, reverse);
+            }
+        }
+    }
+
+    #[test]
+    fn test_unknown_algorithm() {
+        let err = sort_text(&["--algo", "radix"], "1\n").unwrap_err();
+        assert!(matches!(err, CliError::Algorithm(_)));
+        assert!(err.to_string().contains("radix"));
+    }
+
+    #[test]
+    fn test_usage_options_are_contiguous() {
+        let lines: Vec<&str> = USAGE.lines().collect();
+        let header = lines.iter().position(|&line| line == "选项:").unwrap();
+        let options: Vec<usize> = lines
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| line.trim_start().starts_with('-'))
+            .map(|(i, _)| i)
+            .collect();
+        assert_eq!(options.len(), 7);
+        assert_eq!(options, (header + 1..header + 1 + options.len()).collect::<Vec<_>>());
+    }
+}
@@ -1,26 +1,139 @@
-pub fn counting_sort(arr: &mut [usize], max_val: usize) {
+// 测试特性：计数排序、自定义错误类型、Result错误传播、稳定排序
+// 语法要点：enum错误类型、impl fmt::Display / std::error::Error、泛型键提取函数
+// 功能：实现计数排序：自动计算键的取值范围，通过偏移支持负数键，
+//       范围非法时返回错误而不是越界 panic，并支持按键稳定地排序任意记录
+use std::error::Error;
+use std::fmt;
+
+use super::instrument::{NoopObserver, SortObserver};
+use super::radix_sort::{apply_permutation, RadixKey};
+
+// 自动推断范围时允许的最大计数数组长度，防止极大范围耗尽内存
+pub const MAX_COUNTING_RANGE: usize = 1 << 24;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CountingSortError {
+    // 元素超过了调用方给定的最大值
+    ValueOutOfRange { value: usize, max_val: usize },
+    // 键的取值范围 (max - min + 1) 超过了允许的计数数组长度
+    RangeTooLarge { range: u128, limit: usize },
+}
+
+impl fmt::Display for CountingSortError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CountingSortError::ValueOutOfRange { value, max_val } => {
+                write!(f, "元素 {} 超出了最大值 {}", value, max_val)
+            }
+            CountingSortError::RangeTooLarge { range, limit } => {
+                write!(f, "键的取值范围 {} 超过了上限 {}", range, limit)
+            }
+        }
+    }
+}
+
+impl Error for CountingSortError {}
+
+pub fn counting_sort(arr: &mut [usize], max_val: usize) -> Result<(), CountingSortError> {
     let n = arr.len();
     if n == 0 {
-        return;
+        return Ok(());
     }
-    
-    let mut count = vec![0usize; max_val + 1];
-    let mut output = vec![0usize; n];
-    
-    for &item in arr.iter() {
-        count[item] += 1;
+    if let Some(&value) = arr.iter().find(|&&item| item > max_val) {
+        return Err(CountingSortError::ValueOutOfRange { value, max_val });
+    }
+    // max_val 只用于校验。计数数组交给按实际最小值偏移的自动版本分配：
+    // max_val 可能是 usize::MAX，max_val + 1 会溢出，巨大的分配失败也无法捕获，
+    // 而且两个入口应当接受同样的输入
+    counting_sort_auto(arr)
+}
+
+// 自动计算最小值和最大值，适用于任意宽度的有符号/无符号整数
+pub fn counting_sort_auto<K: RadixKey>(arr: &mut [K]) -> Result<(), CountingSortError> {
+    counting_sort_observed(arr, &mut NoopObserver)
+}
+
+pub fn counting_sort_observed<K, O>(arr: &mut [K], observer: &mut O) -> Result<(), CountingSortError>
+where
+    K: RadixKey,
+    O: SortObserver + ?Sized,
+{
+    let n = arr.len();
+    if n <= 1 {
+        return Ok(());
     }
+    let (min, count) = count_keys(arr.iter().map(|k| k.radix_bits()))?;
+    observer.on_alloc(count.len());
+    let mut starts = prefix_sums(count);
     
-    for i in 1..=max_val {
-        count[i] += count[i - 1];
+    // 先算出每个位置应放入的原下标，再原地应用置换
+    observer.on_alloc(n);
+    let mut order = vec![0usize; n];
+    for (index, item) in arr.iter().enumerate() {
+        let slot = &mut starts[(item.radix_bits() - min) as usize];
+        order[*slot] = index;
+        *slot += 1;
+    }
+    apply_permutation(arr, &mut order, observer);
+    Ok(())
+}
+
+// 按整数键稳定排序任意记录，记录本身不需要 Clone
+pub fn counting_sort_by_key<T, K, F>(arr: &mut [T], mut key: F) -> Result<(), CountingSortError>
+where
+    K: RadixKey,
+    F: FnMut(&T) -> K,
+{
+    if arr.len() <= 1 {
+        return Ok(());
     }
+    let keys: Vec<u128> = arr.iter().map(|item| key(item).radix_bits()).collect();
+    let (min, count) = count_keys(keys.iter().copied())?;
+    let mut starts = prefix_sums(count);
     
-    for &item in arr.iter().rev() {
-        count[item] -= 1;
-        output[count[item]] = item;
+    // order[i] 为排序后第 i 个位置上元素的原下标
+    let mut order = vec![0usize; arr.len()];
+    for (index, &k) in keys.iter().enumerate() {
+        let slot = &mut starts[(k - min) as usize];
+        order[*slot] = index;
+        *slot += 1;
+    }
+    apply_permutation(arr, &mut order, &mut NoopObserver);
+    Ok(())
+}
+
+// 统计每个键相对最小值的出现次数。键已映射为保序的无符号值，负数也能直接相减
+fn count_keys<I>(keys: I) -> Result<(u128, Vec<usize>), CountingSortError>
+where
+    I: Iterator<Item = u128> + Clone,
+{
+    let min = keys.clone().min().unwrap_or(0);
+    let max = keys.clone().max().unwrap_or(0);
+    // 键覆盖整个 u128 时 max - min + 1 会溢出，所以先比较差值
+    let span = max - min;
+    if span >= MAX_COUNTING_RANGE as u128 {
+        return Err(CountingSortError::RangeTooLarge {
+            range: span.saturating_add(1),
+            limit: MAX_COUNTING_RANGE,
+        });
     }
     
-    arr.copy_from_slice(&output);
+    let mut count = vec![0usize; span as usize + 1];
+    for k in keys {
+        count[(k - min) as usize] += 1;
+    }
+    Ok((min, count))
+}
+
+// 把计数转换为每个键在输出中的起始位置
+fn prefix_sums(mut count: Vec<usize>) -> Vec<usize> {
+    let mut offset = 0;
+    for c in count.iter_mut() {
+        let bucket_len = *c;
+        *c = offset;
+        offset += bucket_len;
+    }
+    count
 }
 
 #[cfg(test)]
@@ -30,21 +143,85 @@ mod tests {
     #[test]
     fn test_counting_sort() {
         let mut arr = vec![4, 2, 2, 8, 3, 3, 1];
-        counting_sort(&mut arr, 8);
+        counting_sort(&mut arr, 8).unwrap();
         assert_eq!(arr, vec![1, 2, 2, 3, 3, 4, 8]);
     }
 
     #[test]
     fn test_empty_array() {
         let mut arr: Vec<usize> = vec![];
-        counting_sort(&mut arr, 0);
-        assert_eq!(arr, Vec::<i32>::new());
+        counting_sort(&mut arr, 0).unwrap();
+        assert_eq!(arr, Vec::<usize>::new());
     }
 
     #[test]
     fn test_single_element() {
         let mut arr = vec![1];
-        counting_sort(&mut arr, 1);
+        counting_sort(&mut arr, 1).unwrap();
         assert_eq!(arr, vec![1]);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_value_above_max_is_an_error() {
+        let mut arr = vec![3, 9, 1];
+        let err = counting_sort(&mut arr, 5).unwrap_err();
+        assert_eq!(err, CountingSortError::ValueOutOfRange { value: 9, max_val: 5 });
+        assert_eq!(arr, vec![3, 9, 1]);
+    }
+
+    #[test]
+    fn test_huge_max_val_is_not_allocated() {
+        // 上界只用于校验，计数数组按实际最大值分配
+        let mut arr = vec![5, 0, 3];
+        counting_sort(&mut arr, usize::MAX).unwrap();
+        assert_eq!(arr, vec![0, 3, 5]);
+
+        let mut arr = vec![1 << 45, 0];
+        let err = counting_sort(&mut arr, usize::MAX).unwrap_err();
+        assert_eq!(err, CountingSortError::RangeTooLarge { range: (1 << 45) + 1, limit: MAX_COUNTING_RANGE });
+        assert_eq!(arr, vec![1 << 45, 0]);
+
+        // 范围只取决于 max - min，值本身很大也没关系
+        let mut arr = vec![MAX_COUNTING_RANGE + 1, MAX_COUNTING_RANGE];
+        counting_sort(&mut arr, usize::MAX).unwrap();
+        assert_eq!(arr, vec![MAX_COUNTING_RANGE, MAX_COUNTING_RANGE + 1]);
+    }
+
+    #[test]
+    fn test_auto_range_with_negative_keys() {
+        let mut arr = vec![3i32, -5, 0, -5, 12, -1, 7];
+        counting_sort_auto(&mut arr).unwrap();
+        assert_eq!(arr, vec![-5, -5, -1, 0, 3, 7, 12]);
+
+        let mut extremes = vec![i8::MAX, i8::MIN, 0, -1];
+        counting_sort_auto(&mut extremes).unwrap();
+        assert_eq!(extremes, vec![i8::MIN, -1, 0, i8::MAX]);
+
+        // 范围小但数值大时同样可以排序
+        let mut offset = vec![1_000_000_003u64, 1_000_000_001, 1_000_000_002];
+        counting_sort_auto(&mut offset).unwrap();
+        assert_eq!(offset, vec![1_000_000_001, 1_000_000_002, 1_000_000_003]);
+    }
+
+    #[test]
+    fn test_range_too_large_is_an_error() {
+        let mut arr = vec![i64::MIN, i64::MAX];
+        let err = counting_sort_auto(&mut arr).unwrap_err();
+        assert!(matches!(err, CountingSortError::RangeTooLarge { .. }));
+        assert_eq!(arr, vec![i64::MIN, i64::MAX]);
+
+        let mut full = vec![u128::MAX, 0];
+        let err = counting_sort_auto(&mut full).unwrap_err();
+        assert_eq!(err, CountingSortError::RangeTooLarge { range: u128::MAX, limit: MAX_COUNTING_RANGE });
+    }
+
+    #[test]
+    fn test_counting_sort_by_key_is_stable() {
+        let mut scores = vec![("carol", -2), ("alice", 90), ("bob", -2), ("dave", 90), ("eve", 15)];
+        counting_sort_by_key(&mut scores, |&(_, score)| score).unwrap();
+        assert_eq!(
+            scores,
+            vec![("carol", -2), ("bob", -2), ("eve", 15), ("alice", 90), ("dave", 90)]
+        );
+    }
+}
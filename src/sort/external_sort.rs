@@ -0,0 +1,294 @@
+// 测试特性：外部排序、临时文件、多路归并、Read/Write 泛型 I/O
+// 语法要点：BufReader::read_until、Drop 清理临时文件、AtomicUsize 生成唯一文件名、io::Result
+// 功能：对放不进内存的数据排序：按内存预算分块读入记录，用归并排序排好后写成临时有序段，
+//       再用二叉堆（复用堆排序的 heapify）做 k 路归并写到输出
+
+use std::cmp::Ordering;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::mem;
+use std::path::PathBuf;
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+
+use super::heap_sort::heapify;
+use super::instrument::NoopObserver;
+use super::merge_sort::merge_sort_by;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExternalSortConfig {
+    // 每个有序段在内存中最多占用的字节数（记录内容加上每条记录的 Vec 开销）
+    pub memory_budget: usize,
+    // 记录分隔符，输出中每条记录之后都会写一个分隔符
+    pub delimiter: u8,
+    // 存放临时有序段的目录
+    pub temp_dir: PathBuf,
+    // 一次归并最多同时打开的有序段数，超过时先分多趟合并
+    pub max_merge_width: usize,
+}
+
+impl Default for ExternalSortConfig {
+    fn default() -> Self {
+        ExternalSortConfig {
+            memory_budget: 64 * 1024 * 1024,
+            delimiter: b'\n',
+            temp_dir: std::env::temp_dir(),
+            max_merge_width: 64,
+        }
+    }
+}
+
+// 一次外部排序的统计
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExternalSortStats {
+    pub records: usize,
+    // 分块阶段写出的有序段数，全部放得进内存时为 0
+    pub runs: usize,
+    // 写出中间有序段的归并趟数，不含最后写到输出的那一趟
+    pub merge_passes: usize,
+}
+
+// 按字节序排序 input 中的记录并写入 output
+pub fn external_sort<R: Read, W: Write>(
+    input: R,
+    output: W,
+    config: &ExternalSortConfig,
+) -> io::Result<ExternalSortStats> {
+    external_sort_by(input, output, config, |a, b| a.cmp(b))
+}
+
+// 按自定义比较函数排序，相等的记录保持输入中的先后顺序
+pub fn external_sort_by<R, W, F>(
+    input: R,
+    output: W,
+    config: &ExternalSortConfig,
+    mut compare: F,
+) -> io::Result<ExternalSortStats>
+where
+    R: Read,
+    W: Write,
+    F: FnMut(&[u8], &[u8]) -> Ordering,
+{
+    let mut reader = BufReader::new(input);
+    let mut output = BufWriter::new(output);
+    let mut stats = ExternalSortStats::default();
+    let mut runs: Vec<RunFile> = Vec::new();
+    let mut chunk: Vec<Vec<u8>> = Vec::new();
+    let mut chunk_bytes = 0;
+
+    while let Some(record) = read_record(&mut reader, config.delimiter)? {
+        stats.records += 1;
+        chunk_bytes += record.len() + mem::size_of::<Vec<u8>>();
+        chunk.push(record);
+        if chunk_bytes >= config.memory_budget {
+            merge_sort_by(&mut chunk, |a, b| compare(a, b));
+            runs.push(spill(&chunk, config)?);
+            chunk.clear();
+            chunk_bytes = 0;
+        }
+    }
+    merge_sort_by(&mut chunk, |a, b| compare(a, b));
+
+    // 数据全部放得进内存时直接输出，不写临时文件
+    if runs.is_empty() {
+        for record in &chunk {
+            write_record(&mut output, record, config.delimiter)?;
+        }
+        output.flush()?;
+        return Ok(stats);
+    }
+    if !chunk.is_empty() {
+        runs.push(spill(&chunk, config)?);
+    }
+    drop(chunk);
+    stats.runs = runs.len();
+
+    // 有序段太多时先分组合并成更少的段；每组内保持段的先后顺序以维持稳定性
+    let width = config.max_merge_width.max(2);
+    while runs.len() > width {
+        let mut merged = Vec::with_capacity(runs.len().div_ceil(width));
+        let mut pending = runs.into_iter().peekable();
+        while pending.peek().is_some() {
+            let group: Vec<RunFile> = pending.by_ref().take(width).collect();
+            let run = RunFile::create(config)?;
+            let mut writer = BufWriter::new(File::create(&run.path)?);
+            merge_runs(&group, &mut writer, config.delimiter, &mut compare)?;
+            writer.flush()?;
+            merged.push(run);
+        }
+        runs = merged;
+        stats.merge_passes += 1;
+    }
+
+    merge_runs(&runs, &mut output, config.delimiter, &mut compare)?;
+    output.flush()?;
+    Ok(stats)
+}
+
+// 读取一条记录（不含分隔符），输入结束时返回 None
+fn read_record<R: BufRead>(reader: &mut R, delimiter: u8) -> io::Result<Option<Vec<u8>>> {
+    let mut record = Vec::new();
+    if reader.read_until(delimiter, &mut record)? == 0 {
+        return Ok(None);
+    }
+    if record.last() == Some(&delimiter) {
+        record.pop();
+    }
+    Ok(Some(record))
+}
+
+fn write_record<W: Write>(writer: &mut W, record: &[u8], delimiter: u8) -> io::Result<()> {
+    writer.write_all(record)?;
+    writer.write_all(&[delimiter])
+}
+
+// 临时有序段文件，离开作用域时自动删除
+struct RunFile {
+    path: PathBuf,
+}
+
+impl RunFile {
+    fn create(config: &ExternalSortConfig) -> io::Result<RunFile> {
+        static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+        let id = NEXT_ID.fetch_add(1, AtomicOrdering::Relaxed);
+        let path = config.temp_dir.join(format!("synax-run-{}-{}.tmp", process::id(), id));
+        File::create(&path)?;
+        Ok(RunFile { path })
+    }
+}
+
+impl Drop for RunFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+fn spill(chunk: &[Vec<u8>], config: &ExternalSortConfig) -> io::Result<RunFile> {
+    let run = RunFile::create(config)?;
+    let mut writer = BufWriter::new(File::create(&run.path)?);
+    for record in chunk {
+        write_record(&mut writer, record, config.delimiter)?;
+    }
+    writer.flush()?;
+    Ok(run)
+}
+
+// k 路归并：堆中保存每个有序段的当前记录及段号。
+// heapify 维护的是大顶堆，因此把比较反过来，堆顶就是最小的记录；
+// 记录相等时段号小的优先，保证稳定
+fn merge_runs<W, F>(runs: &[RunFile], output: &mut W, delimiter: u8, compare: &mut F) -> io::Result<()>
+where
+    W: Write,
+    F: FnMut(&[u8], &[u8]) -> Ordering,
+{
+    let mut readers = Vec::with_capacity(runs.len());
+    for run in runs {
+        readers.push(BufReader::new(File::open(&run.path)?));
+    }
+
+    let mut heap: Vec<(Vec<u8>, usize)> = Vec::with_capacity(readers.len());
+    for (source, reader) in readers.iter_mut().enumerate() {
+        if let Some(record) = read_record(reader, delimiter)? {
+            heap.push((record, source));
+        }
+    }
+
+    let mut min_first =
+        |a: &(Vec<u8>, usize), b: &(Vec<u8>, usize)| compare(&b.0, &a.0).then_with(|| b.1.cmp(&a.1));
+    let n = heap.len();
+    for i in (0..n / 2).rev() {
+        heapify(&mut heap, n, i, &mut min_first, &mut NoopObserver, 0);
+    }
+
+    while !heap.is_empty() {
+        let source = heap[0].1;
+        write_record(output, &heap[0].0, delimiter)?;
+        match read_record(&mut readers[source], delimiter)? {
+            Some(record) => heap[0].0 = record,
+            None => {
+                heap.swap_remove(0);
+            }
+        }
+        let n = heap.len();
+        heapify(&mut heap, n, 0, &mut min_first, &mut NoopObserver, 0);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sort::rng::Rng;
+
+    // 每个测试使用独立的临时目录，便于检查临时文件是否被清理
+    fn test_config(name: &str, memory_budget: usize) -> ExternalSortConfig {
+        let temp_dir = std::env::temp_dir().join(format!("synax-external-{}-{}", name, process::id()));
+        fs::create_dir_all(&temp_dir).unwrap();
+        ExternalSortConfig {
+            memory_budget,
+            temp_dir,
+            ..ExternalSortConfig::default()
+        }
+    }
+
+    fn assert_cleaned(config: &ExternalSortConfig) {
+        assert_eq!(fs::read_dir(&config.temp_dir).unwrap().count(), 0);
+        fs::remove_dir(&config.temp_dir).unwrap();
+    }
+
+    fn sort_bytes(input: &[u8], config: &ExternalSortConfig) -> (Vec<u8>, ExternalSortStats) {
+        let mut output = Vec::new();
+        let stats = external_sort(input, &mut output, config).unwrap();
+        (output, stats)
+    }
+
+    #[test]
+    fn test_fits_in_memory() {
+        let config = test_config("memory", 1 << 20);
+        let (output, stats) = sort_bytes(b"pear\nfig\nbanana\nkiwi", &config);
+        assert_eq!(output, b"banana\nfig\nkiwi\npear\n");
+        assert_eq!(stats, ExternalSortStats { records: 4, runs: 0, merge_passes: 0 });
+        assert_cleaned(&config);
+    }
+
+    #[test]
+    fn test_empty_input() {
+        let config = test_config("empty", 1 << 20);
+        let (output, stats) = sort_bytes(b"", &config);
+        assert!(output.is_empty());
+        assert_eq!(stats.records, 0);
+        assert_cleaned(&config);
+    }
+
+    #[test]
+    fn test_spills_and_merges_runs() {
+        let mut rng = Rng::new(11);
+        let lines: Vec<String> = (0..2_000).map(|_| format!("{:08}", rng.below(100_000))).collect();
+        let input = lines.join("\n");
+        let mut expected = lines.clone();
+        expected.sort();
+
+        // 每条记录约 32 字节，预算 1 KiB 时产生约 60 个有序段
+        let mut config = test_config("spill", 1024);
+        config.max_merge_width = 4;
+        let (output, stats) = sort_bytes(input.as_bytes(), &config);
+        assert_eq!(String::from_utf8(output).unwrap(), expected.join("\n") + "\n");
+        assert_eq!(stats.records, 2_000);
+        assert!(stats.runs > 16, "{:?}", stats);
+        assert!(stats.merge_passes >= 2, "{:?}", stats);
+        assert_cleaned(&config);
+    }
+
+    #[test]
+    fn test_custom_delimiter_and_stable_comparator() {
+        let mut config = test_config("delimiter", 64);
+        config.delimiter = b',';
+        // 只比较第一个字节，相同首字母的记录保持原顺序
+        let input = b"b1,a1,c1,a2,b2,a3,c2,b3";
+        let mut output = Vec::new();
+        let stats = external_sort_by(&input[..], &mut output, &config, |a, b| a[..1].cmp(&b[..1])).unwrap();
+        assert_eq!(output, b"a1,a2,a3,b1,b2,b3,c1,c2,");
+        assert!(stats.runs > 1);
+        assert_cleaned(&config);
+    }
+}
@@ -1,32 +1,83 @@
+use std::cmp::Ordering;
+
+use super::instrument::{NoopObserver, SortObserver};
+
 pub fn heap_sort<T: PartialOrd>(arr: &mut [T]) {
+    heap_sort_by(arr, |a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+}
+
+pub fn heap_sort_desc<T: PartialOrd>(arr: &mut [T]) {
+    heap_sort_by(arr, |a, b| b.partial_cmp(a).unwrap_or(Ordering::Equal));
+}
+
+pub fn heap_sort_by<T, F>(arr: &mut [T], compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    heap_sort_observed(arr, compare, &mut NoopObserver);
+}
+
+pub fn heap_sort_observed<T, F, O>(arr: &mut [T], mut compare: F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver + ?Sized,
+{
+    heap_sort_at(arr, &mut compare, observer, 0);
+}
+
+pub(crate) fn heap_sort_at<T, F, O>(arr: &mut [T], compare: &mut F, observer: &mut O, base: usize)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver + ?Sized,
+{
     let n = arr.len();
     
     for i in (0..n / 2).rev() {
-        heapify(arr, n, i);
+        heapify(arr, n, i, compare, observer, base);
     }
     
     for i in (0..n).rev() {
+        observer.on_swap(base, base + i);
         arr.swap(0, i);
-        heapify(arr, i, 0);
+        heapify(arr, i, 0, compare, observer, base);
     }
 }
 
-fn heapify<T: PartialOrd>(arr: &mut [T], n: usize, i: usize) {
+pub fn heap_sort_by_key<T, K, F>(arr: &mut [T], mut key: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    heap_sort_by(arr, |a, b| key(a).cmp(&key(b)));
+}
+
+pub(crate) fn heapify<T, F, O>(arr: &mut [T], n: usize, i: usize, compare: &mut F, observer: &mut O, base: usize)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver + ?Sized,
+{
     let mut largest = i;
     let left = 2 * i + 1;
     let right = 2 * i + 2;
     
-    if left < n && arr[left] > arr[largest] {
-        largest = left;
+    if left < n {
+        observer.on_compare(base + left, base + largest);
+        if compare(&arr[left], &arr[largest]) == Ordering::Greater {
+            largest = left;
+        }
     }
     
-    if right < n && arr[right] > arr[largest] {
-        largest = right;
+    if right < n {
+        observer.on_compare(base + right, base + largest);
+        if compare(&arr[right], &arr[largest]) == Ordering::Greater {
+            largest = right;
+        }
     }
     
     if largest != i {
+        observer.on_swap(base + i, base + largest);
         arr.swap(i, largest);
-        heapify(arr, n, largest);
+        heapify(arr, n, largest, compare, observer, base);
     }
 }
 
@@ -54,4 +105,4 @@ mod tests {
         heap_sort(&mut arr);
         assert_eq!(arr, vec![1]);
     }
-}
\ No newline at end of file
+}
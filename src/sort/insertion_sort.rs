@@ -1,15 +1,59 @@
-pub fn insertion_sort<T: PartialOrd + Clone>(arr: &mut [T]) {
+use std::cmp::Ordering;
+
+use super::instrument::{NoopObserver, SortObserver};
+
+pub fn insertion_sort<T: PartialOrd>(arr: &mut [T]) {
+    insertion_sort_by(arr, |a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+}
+
+pub fn insertion_sort_desc<T: PartialOrd>(arr: &mut [T]) {
+    insertion_sort_by(arr, |a, b| b.partial_cmp(a).unwrap_or(Ordering::Equal));
+}
+
+pub fn insertion_sort_by<T, F>(arr: &mut [T], compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    insertion_sort_observed(arr, compare, &mut NoopObserver);
+}
+
+pub fn insertion_sort_observed<T, F, O>(arr: &mut [T], mut compare: F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver + ?Sized,
+{
+    insertion_sort_at(arr, &mut compare, observer, 0);
+}
+
+// 通过相邻交换把元素逐步前移，不需要 Clone；base 为 arr 在整个切片中的起始下标，
+// 供快速排序、归并排序等在子区间上调用时正确报告位置
+pub(crate) fn insertion_sort_at<T, F, O>(arr: &mut [T], compare: &mut F, observer: &mut O, base: usize)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver + ?Sized,
+{
     for i in 1..arr.len() {
-        let key = arr[i].clone();
         let mut j = i;
-        while j > 0 && arr[j - 1] > key {
-            arr[j] = arr[j - 1].clone();
+        while j > 0 {
+            observer.on_compare(base + j - 1, base + j);
+            if compare(&arr[j - 1], &arr[j]) != Ordering::Greater {
+                break;
+            }
+            observer.on_swap(base + j - 1, base + j);
+            arr.swap(j - 1, j);
             j -= 1;
         }
-        arr[j] = key;
     }
 }
 
+pub fn insertion_sort_by_key<T, K, F>(arr: &mut [T], mut key: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    insertion_sort_by(arr, |a, b| key(a).cmp(&key(b)));
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -34,4 +78,4 @@ mod tests {
         insertion_sort(&mut arr);
         assert_eq!(arr, vec![1]);
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,187 @@
+// 测试特性：观察者模式、Trait默认方法、?Sized泛型约束、操作计数
+// 语法要点：trait SortObserver { fn on_compare(..) {} }、O: SortObserver + ?Sized、&mut dyn Trait
+// 功能：排序算法在比较、交换、写入和分配辅助空间时通知观察者，
+//       SortStats 统计这些操作次数，用于生成经验复杂度表和发现性能回退
+
+use std::fmt;
+
+use super::sorter::{Registry, Sorter};
+
+// 排序过程中的操作回调，下标均相对于被排序的整个切片。
+// 所有方法默认什么都不做，实现者只需覆盖关心的事件
+pub trait SortObserver {
+    // 比较了位置 i 和 j 上的元素
+    fn on_compare(&mut self, _i: usize, _j: usize) {}
+
+    // 交换了位置 i 和 j 上的元素
+    fn on_swap(&mut self, _i: usize, _j: usize) {}
+
+    // 把当前合并开始时位于 from 的元素写入位置 i（不含交换），只在 on_merge 之后出现
+    fn on_write(&mut self, _i: usize, _from: usize) {}
+
+    // 分配了能容纳 len 个元素的辅助空间
+    fn on_alloc(&mut self, _len: usize) {}
+
+    // 快速排序把 [start, end) 划分为 < 主元的 [start, lt)、== 主元的 [lt, gt) 和 > 主元的 [gt, end)
+    fn on_partition(&mut self, _start: usize, _lt: usize, _gt: usize, _end: usize) {}
+
+    // 开始合并有序区间 [start, mid) 与 [mid, end)
+    fn on_merge(&mut self, _start: usize, _mid: usize, _end: usize) {}
+}
+
+// 不做任何事的观察者，普通排序函数使用它，编译后没有额外开销
+pub struct NoopObserver;
+
+impl SortObserver for NoopObserver {}
+
+// 一次排序的操作统计
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SortStats {
+    pub comparisons: usize,
+    pub swaps: usize,
+    // 写回数组位置的次数，不含交换
+    pub moves: usize,
+    // 辅助空间的分配次数及总元素数
+    pub allocations: usize,
+    pub allocated_elements: usize,
+}
+
+impl SortObserver for SortStats {
+    fn on_compare(&mut self, _i: usize, _j: usize) {
+        self.comparisons += 1;
+    }
+
+    fn on_swap(&mut self, _i: usize, _j: usize) {
+        self.swaps += 1;
+    }
+
+    fn on_write(&mut self, _i: usize, _from: usize) {
+        self.moves += 1;
+    }
+
+    fn on_alloc(&mut self, len: usize) {
+        self.allocations += 1;
+        self.allocated_elements += len;
+    }
+}
+
+impl fmt::Display for SortStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "比较: {}, 交换: {}, 移动: {}, 分配: {} 次/{} 个元素",
+            self.comparisons, self.swaps, self.moves, self.allocations, self.allocated_elements
+        )
+    }
+}
+
+// 用任意排序器排序并返回操作统计
+pub fn measure<T>(sorter: &dyn Sorter<T>, arr: &mut [T]) -> SortStats {
+    let mut stats = SortStats::default();
+    sorter.sort_observed(arr, &mut stats);
+    stats
+}
+
+// 对注册表中的每个算法分别排序 input 的一份拷贝，返回各自的统计
+pub fn measure_all<T: Clone>(registry: &Registry<T>, input: &[T]) -> Vec<(&'static str, SortStats)> {
+    registry
+        .iter()
+        .map(|sorter| {
+            let mut data = input.to_vec();
+            (sorter.name(), measure(sorter, &mut data))
+        })
+        .collect()
+}
+
+// 生成经验复杂度表：每行一个算法，每列一个输入规模，单元格为比较次数/交换次数/移动次数
+pub fn complexity_table<T: Clone>(registry: &Registry<T>, inputs: &[Vec<T>]) -> String {
+    let mut table = format!("{:<10}", "算法");
+    for input in inputs {
+        table.push_str(&format!("{:>24}", format!("n={}", input.len())));
+    }
+    table.push('\n');
+
+    let columns: Vec<_> = inputs.iter().map(|input| measure_all(registry, input)).collect();
+    for (row, sorter) in registry.iter().enumerate() {
+        table.push_str(&format!("{:<10}", sorter.name()));
+        for column in &columns {
+            let stats = column[row].1;
+            let cell = format!("{}/{}/{}", stats.comparisons, stats.swaps, stats.moves);
+            table.push_str(&format!("{:>24}", cell));
+        }
+        table.push('\n');
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sort::sorter::{BubbleSort, HeapSort, InsertionSort, MergeSort, RadixSort, SelectionSort};
+
+    fn reversed(n: u32) -> Vec<u32> {
+        (0..n).rev().collect()
+    }
+
+    #[test]
+    fn test_quadratic_sorts_on_reversed_input() {
+        // 逆序 10 个元素：冒泡/插入需要 45 次比较和 45 次交换
+        let bubble = measure(&BubbleSort, &mut reversed(10));
+        assert_eq!(bubble, SortStats { comparisons: 45, swaps: 45, ..SortStats::default() });
+
+        let insertion = measure(&InsertionSort, &mut reversed(10));
+        assert_eq!((insertion.comparisons, insertion.swaps), (45, 45));
+
+        // 选择排序比较次数固定为 n(n-1)/2，交换最多 n-1 次
+        let selection = measure(&SelectionSort, &mut reversed(10));
+        assert_eq!(selection.comparisons, 45);
+        assert!(selection.swaps <= 9);
+    }
+
+    #[test]
+    fn test_sorted_input_is_cheap_for_adaptive_sorts() {
+        let mut sorted: Vec<u32> = (0..100).collect();
+        let insertion = measure(&InsertionSort, &mut sorted);
+        assert_eq!((insertion.comparisons, insertion.swaps), (99, 0));
+
+        // 两半已经有序时归并排序跳过合并，只分配一次暂存区。
+        // 叶子区间由固定的换位网络处理：12 段长 6、4 段长 7，共 264 次比较，另有 15 次跳过检查
+        let merge = measure(&MergeSort, &mut sorted);
+        assert_eq!(merge.comparisons, 264 + 15);
+        assert_eq!((merge.swaps, merge.moves), (0, 0));
+        assert_eq!(merge.allocations, 1);
+    }
+
+    #[test]
+    fn test_every_registered_sorter_reports_work() {
+        let input = reversed(64);
+        let registry = Registry::<u32>::all();
+        let results = measure_all(&registry, &input);
+        assert_eq!(results.len(), registry.len());
+        for (name, stats) in results {
+            assert!(stats.swaps + stats.moves > 0, "{}: {}", name, stats);
+        }
+
+        // 非比较排序不做任何比较
+        let radix = measure(&RadixSort, &mut reversed(64));
+        assert_eq!(radix.comparisons, 0);
+        assert!(radix.allocations >= 1);
+    }
+
+    #[test]
+    fn test_heap_sort_stays_n_log_n() {
+        let n = 1024u32;
+        let stats = measure(&HeapSort, &mut reversed(n));
+        // 2 n log2 n 是堆排序比较次数的上界
+        assert!(stats.comparisons <= 2 * 1024 * 10, "{}", stats);
+    }
+
+    #[test]
+    fn test_complexity_table() {
+        let table = complexity_table(&Registry::<u32>::all(), &[reversed(8), reversed(16)]);
+        let lines: Vec<_> = table.lines().collect();
+        assert_eq!(lines.len(), 1 + Registry::<u32>::all().len());
+        assert!(lines[0].contains("n=8") && lines[0].contains("n=16"));
+        assert!(lines[1].starts_with("bubble") && lines[1].contains("28/28/0"));
+    }
+}
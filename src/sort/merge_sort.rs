@@ -1,53 +1,240 @@
-// 测试特性：归并排序算法、Clone trait、向量操作、稳定排序
-// 语法要点：<T: PartialOrd + Clone>、to_vec()、clone()方法
-// 功能：实现稳定的归并排序算法，需要Clone trait支持向量复制
-pub fn merge_sort<T: PartialOrd + Clone>(arr: &mut [T]) {
-    if arr.len() <= 1 {
+// 测试特性：归并排序算法、稳定排序、unsafe指针操作、Drop守卫(panic安全)
+// 语法要点：Vec::with_capacity作为暂存区、ptr::copy_nonoverlapping、impl Drop
+// 功能：实现稳定的归并排序，整个排序只分配一次暂存区(或复用调用方提供的缓冲区)，
+//       通过移动而非克隆元素工作，并提供不使用递归的自底向上版本
+use std::cmp::Ordering;
+use std::mem;
+use std::ptr;
+
+use super::instrument::{NoopObserver, SortObserver};
+use super::sort_network::transposition_sort_at;
+
+// 不超过该长度的区间用稳定的奇偶换位网络排序，不再继续递归
+const NETWORK_THRESHOLD: usize = 8;
+
+pub fn merge_sort<T: PartialOrd>(arr: &mut [T]) {
+    merge_sort_by(arr, |a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+}
+
+pub fn merge_sort_desc<T: PartialOrd>(arr: &mut [T]) {
+    merge_sort_by(arr, |a, b| b.partial_cmp(a).unwrap_or(Ordering::Equal));
+}
+
+pub fn merge_sort_by<T, F>(arr: &mut [T], compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    merge_sort_observed(arr, compare, &mut NoopObserver);
+}
+
+pub fn merge_sort_observed<T, F, O>(arr: &mut [T], mut compare: F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver + ?Sized,
+{
+    let mut buffer = Vec::new();
+    sort_with_buffer(arr, &mut buffer, &mut compare, observer);
+}
+
+pub fn merge_sort_by_key<T, K, F>(arr: &mut [T], mut key: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    merge_sort_by(arr, |a, b| key(a).cmp(&key(b)));
+}
+
+// 使用调用方提供的缓冲区作为暂存区，多次排序时可以复用同一块内存。
+// 缓冲区会先被清空，排序结束后长度为 0，但保留已分配的容量
+pub fn merge_sort_with_buffer<T, F>(arr: &mut [T], buffer: &mut Vec<T>, mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    sort_with_buffer(arr, buffer, &mut compare, &mut NoopObserver);
+}
+
+fn sort_with_buffer<T, F, O>(arr: &mut [T], buffer: &mut Vec<T>, compare: &mut F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver + ?Sized,
+{
+    if arr.len() <= 1 || mem::size_of::<T>() == 0 {
+        return;
+    }
+    buffer.clear();
+    if buffer.capacity() < arr.len() / 2 {
+        observer.on_alloc(arr.len() / 2);
+        buffer.reserve(arr.len() / 2);
+    }
+    merge_sort_impl(arr, buffer.as_mut_ptr(), compare, observer, 0);
+}
+
+pub fn merge_sort_bottom_up<T: PartialOrd>(arr: &mut [T]) {
+    merge_sort_bottom_up_by(arr, |a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+}
+
+pub fn merge_sort_bottom_up_by<T, F>(arr: &mut [T], compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    merge_sort_bottom_up_observed(arr, compare, &mut NoopObserver);
+}
+
+// 自底向上的迭代归并：子序列宽度从 1 开始逐轮翻倍，没有递归调用
+pub fn merge_sort_bottom_up_observed<T, F, O>(arr: &mut [T], mut compare: F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver + ?Sized,
+{
+    let len = arr.len();
+    if len <= 1 || mem::size_of::<T>() == 0 {
+        return;
+    }
+    // 左半部分最长为小于 len 的最大的 2 的幂
+    let capacity = len.next_power_of_two() / 2;
+    observer.on_alloc(capacity);
+    let mut buffer: Vec<T> = Vec::with_capacity(capacity);
+    let mut width = 1;
+    while width < len {
+        let mut start = 0;
+        while start + width < len {
+            let end = (start + 2 * width).min(len);
+            // SAFETY: 缓冲区容量不小于 width
+            unsafe {
+                merge_halves(&mut arr[start..end], width, buffer.as_mut_ptr(), &mut compare, observer, start)
+            };
+            start = end;
+        }
+        width *= 2;
+    }
+}
+
+// buf 必须至少能容纳 arr.len() / 2 个元素；base 为 arr 在整个切片中的起始下标
+fn merge_sort_impl<T, F, O>(arr: &mut [T], buf: *mut T, compare: &mut F, observer: &mut O, base: usize)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver + ?Sized,
+{
+    let len = arr.len();
+    if len <= NETWORK_THRESHOLD {
+        // 这里刻意不用 network_sort_at 的最优网络：它们会交换不相邻的元素，
+        // 打乱相等元素的顺序。换位网络要多付出 n(n-1)/2 次比较，换来归并排序的稳定性
+        transposition_sort_at(arr, compare, observer, base);
         return;
     }
     
-    let mid = arr.len() / 2;
-    let mut left = arr[..mid].to_vec();
-    let mut right = arr[mid..].to_vec();
-    
-    merge_sort(&mut left);
-    merge_sort(&mut right);
+    let mid = len / 2;
+    merge_sort_impl(&mut arr[..mid], buf, compare, observer, base);
+    merge_sort_impl(&mut arr[mid..], buf, compare, observer, base + mid);
     
-    merge(arr, &left, &right);
+    // SAFETY: 左半部分长度为 len / 2，不超过缓冲区容量
+    unsafe { merge_halves(arr, mid, buf, compare, observer, base) };
 }
 
-fn merge<T: PartialOrd + Clone>(arr: &mut [T], left: &[T], right: &[T]) {
-    let mut i = 0;
-    let mut j = 0;
-    let mut k = 0;
+// 合并有序的 arr[..mid] 与 arr[mid..]。
+// 先把左半部分移到暂存区，再从前往后写回 arr；写入位置永远不会超过右侧的读取位置。
+//
+// SAFETY: buf 必须指向至少 mid 个元素的空闲空间，且与 arr 不重叠
+pub(crate) unsafe fn merge_halves<T, F, O>(
+    arr: &mut [T],
+    mid: usize,
+    buf: *mut T,
+    compare: &mut F,
+    observer: &mut O,
+    base: usize,
+) where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver + ?Sized,
+{
+    let len = arr.len();
+    if mid == 0 || mid >= len || mem::size_of::<T>() == 0 {
+        return;
+    }
+    observer.on_merge(base, base + mid, base + len);
+    // 两半已经整体有序时无需合并
+    observer.on_compare(base + mid - 1, base + mid);
+    if compare(&arr[mid - 1], &arr[mid]) != Ordering::Greater {
+        return;
+    }
     
-    while i < left.len() && j < right.len() {
-        if left[i] <= right[j] {
-            arr[k] = left[i].clone();
-            i += 1;
+    let v = arr.as_mut_ptr();
+    ptr::copy_nonoverlapping(v, buf, mid);
+    let mut hole = MergeHole {
+        start: buf,
+        end: buf.add(mid),
+        dest: v,
+    };
+    // left 为暂存区中的读取位置，right 为右半部分的读取位置，dest 为写入位置
+    let (mut left, mut right, mut dest) = (0, mid, 0);
+    
+    while left < mid && right < len {
+        // 只有右侧严格更小时才取右侧元素，保证稳定性
+        observer.on_compare(base + left, base + right);
+        let take_right = compare(&*v.add(right), &*buf.add(left)) == Ordering::Less;
+        observer.on_write(base + dest, base + if take_right { right } else { left });
+        if take_right {
+            ptr::copy_nonoverlapping(v.add(right), v.add(dest), 1);
+            right += 1;
         } else {
-            arr[k] = right[j].clone();
-            j += 1;
+            ptr::copy_nonoverlapping(buf.add(left), v.add(dest), 1);
+            left += 1;
+            hole.start = buf.add(left);
         }
-        k += 1;
+        dest += 1;
+        hole.dest = v.add(dest);
     }
-    
-    while i < left.len() {
-        arr[k] = left[i].clone();
-        i += 1;
-        k += 1;
+    for offset in 0..mid - left {
+        observer.on_write(base + dest + offset, base + left + offset);
     }
-    
-    while j < right.len() {
-        arr[k] = right[j].clone();
-        j += 1;
-        k += 1;
+    // hole 在此被 drop，把暂存区里剩余的左侧元素移回 arr
+}
+
+// 暂存区中尚未写回的元素 [start, end) 以及它们在 arr 中的目标位置 dest。
+// 即使比较函数 panic，Drop 也会把元素移回去，保证每个元素恰好出现一次
+pub(crate) struct MergeHole<T> {
+    pub(crate) start: *mut T,
+    pub(crate) end: *mut T,
+    pub(crate) dest: *mut T,
+}
+
+impl<T> Drop for MergeHole<T> {
+    fn drop(&mut self) {
+        unsafe {
+            let remaining = self.end.offset_from(self.start) as usize;
+            ptr::copy_nonoverlapping(self.start, self.dest, remaining);
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::sort::rng::Rng;
+    use std::panic::{self, AssertUnwindSafe};
+
+    // 没有实现 Clone 的元素类型
+    #[derive(Debug, PartialEq)]
+    struct Record {
+        key: u32,
+        id: usize,
+    }
+
+    fn records(keys: &[u32]) -> Vec<Record> {
+        keys.iter()
+            .enumerate()
+            .map(|(id, &key)| Record { key, id })
+            .collect()
+    }
+
+    fn random_keys(len: usize, modulo: u32) -> Vec<u32> {
+        let mut rng = Rng::new(0x1234_5678);
+        (0..len).map(|_| rng.below(modulo as u64) as u32).collect()
+    }
+
+    fn is_stably_sorted(arr: &[Record]) -> bool {
+        arr.windows(2)
+            .all(|w| w[0].key < w[1].key || (w[0].key == w[1].key && w[0].id < w[1].id))
+    }
 
     #[test]
     fn test_merge_sort() {
@@ -69,4 +256,73 @@ mod tests {
         merge_sort(&mut arr);
         assert_eq!(arr, vec![1]);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_non_clone_records_are_stable() {
+        for len in [2, 3, 7, 64, 1000] {
+            let keys = random_keys(len, 10);
+            let mut arr = records(&keys);
+            merge_sort_by_key(&mut arr, |r| r.key);
+            assert!(is_stably_sorted(&arr));
+
+            let mut arr = records(&keys);
+            merge_sort_bottom_up_by(&mut arr, |a, b| a.key.cmp(&b.key));
+            assert!(is_stably_sorted(&arr));
+        }
+    }
+
+    #[test]
+    fn test_bottom_up_matches_top_down() {
+        for len in [0, 1, 2, 5, 9, 16, 17, 1000, 1025] {
+            let mut top_down = random_keys(len, 1000);
+            let mut bottom_up = top_down.clone();
+            merge_sort(&mut top_down);
+            merge_sort_bottom_up(&mut bottom_up);
+            assert_eq!(top_down, bottom_up);
+        }
+    }
+
+    #[test]
+    fn test_reuses_caller_buffer() {
+        let mut buffer: Vec<String> = Vec::new();
+        let mut words: Vec<String> = ["pear", "fig", "apple", "kiwi"].iter().map(|s| s.to_string()).collect();
+        merge_sort_with_buffer(&mut words, &mut buffer, |a, b| a.cmp(b));
+        assert_eq!(words, vec!["apple", "fig", "kiwi", "pear"]);
+        assert!(buffer.is_empty());
+        let capacity = buffer.capacity();
+        assert!(capacity >= 2);
+
+        let mut more: Vec<String> = ["b", "a", "d", "c"].iter().map(|s| s.to_string()).collect();
+        merge_sort_with_buffer(&mut more, &mut buffer, |a, b| a.cmp(b));
+        assert_eq!(more, vec!["a", "b", "c", "d"]);
+        assert_eq!(buffer.capacity(), capacity);
+    }
+
+    #[test]
+    fn test_panicking_comparator_keeps_all_elements() {
+        let mut arr: Vec<String> = (0..100).rev().map(|i| i.to_string()).collect();
+        let mut calls = 0;
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            merge_sort_by(&mut arr, |a, b| {
+                calls += 1;
+                if calls == 300 {
+                    panic!("comparator failure");
+                }
+                a.cmp(b)
+            });
+        }));
+        assert!(result.is_err());
+
+        let mut values: Vec<u32> = arr.iter().map(|s| s.parse().unwrap()).collect();
+        values.sort();
+        assert_eq!(values, (0..100).collect::<Vec<u32>>());
+    }
+
+    #[test]
+    fn test_zero_sized_elements() {
+        let mut arr = vec![(); 10];
+        merge_sort(&mut arr);
+        merge_sort_bottom_up(&mut arr);
+        assert_eq!(arr.len(), 10);
+    }
+}
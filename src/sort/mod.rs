@@ -8,32 +8,148 @@ pub mod insertion_sort;
 pub mod merge_sort;
 pub mod quick_sort;
 pub mod heap_sort;
+pub mod tim_sort;
 pub mod radix_sort;
 pub mod counting_sort;
 pub mod shell_sort;
 pub mod bucket_sort;
+pub mod sort_network;
+pub mod sorter;
+pub mod parallel_sort;
+pub mod select;
+pub mod sorted;
+pub mod instrument;
+pub mod trace;
+pub mod rng;
+pub mod bench;
+pub mod verify;
+pub mod external_sort;
+pub mod cli;
 
 // 重新导出常用的排序函数
-pub use bubble_sort::bubble_sort;
-pub use quick_sort::quick_sort;
-pub use merge_sort::merge_sort;
+pub use bubble_sort::{bubble_sort, bubble_sort_by, bubble_sort_by_key, bubble_sort_desc};
+pub use selection_sort::{selection_sort, selection_sort_by, selection_sort_by_key, selection_sort_desc};
+pub use insertion_sort::{insertion_sort, insertion_sort_by, insertion_sort_by_key, insertion_sort_desc};
+pub use quick_sort::{quick_sort, quick_sort_by, quick_sort_by_key, quick_sort_desc};
+pub use merge_sort::{merge_sort, merge_sort_by, merge_sort_by_key, merge_sort_desc};
+pub use heap_sort::{heap_sort, heap_sort_by, heap_sort_by_key, heap_sort_desc};
+pub use tim_sort::{tim_sort, tim_sort_by, tim_sort_by_key, tim_sort_desc};
+pub use radix_sort::{msd_radix_sort, radix_sort, radix_sort_by_key, RadixKey};
+pub use counting_sort::{counting_sort, counting_sort_auto, counting_sort_by_key, CountingSortError};
+pub use shell_sort::{shell_sort, shell_sort_by, shell_sort_by_key, shell_sort_desc, shell_sort_with, shell_sort_with_by, GapSequence};
+pub use bucket_sort::{bucket_sort, bucket_sort_by_key, bucket_sort_by_key_with, bucket_sort_with};
+pub use sorted::{
+    dedup_sorted, difference, equal_range, equal_range_by, intersection, kmerge, kmerge_by, lower_bound, lower_bound_by,
+    merge_sorted, merge_sorted_by, union, upper_bound, upper_bound_by,
+};
+pub use sort_network::{sort_network, sort_network_by, MAX_NETWORK_SIZE};
+pub use sorter::{Registry, SortInfo, Sorter, ALGORITHMS};
+pub use parallel_sort::{par_merge_sort, par_quick_sort, ParallelConfig};
+pub use select::{nth_element, nth_element_by, nth_element_by_key, partial_sort, partial_sort_by, partial_sort_by_key, top_k, top_k_by, top_k_by_key};
+pub use instrument::{measure, NoopObserver, SortObserver, SortStats};
+pub use trace::{Trace, TraceEvent, TraceRecorder};
+pub use rng::Rng;
+pub use bench::{BenchConfig, BenchResult, Distribution};
+pub use external_sort::{external_sort, external_sort_by, ExternalSortConfig, ExternalSortStats};
+pub use verify::{run_properties, verify_sorter, verify_stable, PropertyConfig, Violation};
 
-// 演示函数：测试所有排序算法
+// 演示函数：通过注册表运行所有适用的排序算法
 pub fn demo_all_sorts() {
     println!("=== 排序算法演示 ===");
     
-    let mut test_data = vec![64, 34, 25, 12, 22, 11, 90];
+    let test_data: Vec<u32> = vec![64, 34, 25, 12, 22, 11, 90];
     println!("原始数据: {:?}", test_data);
     
-    let mut bubble_data = test_data.clone();
-    bubble_sort(&mut bubble_data);
-    println!("冒泡排序结果: {:?}", bubble_data);
-    
-    let mut quick_data = test_data.clone();
-    quick_sort(&mut quick_data);
-    println!("快速排序结果: {:?}", quick_data);
-    
-    let mut merge_data = test_data.clone();
-    merge_sort(&mut merge_data);
-    println!("归并排序结果: {:?}", merge_data);
-}
\ No newline at end of file
+    let registry = Registry::<u32>::all();
+    for sorter in registry.iter() {
+        let mut data = test_data.clone();
+        let stats = measure(sorter, &mut data);
+        println!(
+            "{:<10} 稳定: {:<5} 平均: {:<11} 结果: {:?}",
+            sorter.name(),
+            sorter.is_stable(),
+            sorter.complexity().average,
+            data
+        );
+        println!("{:<10} {}", "", stats);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+
+    use super::sorter::find_algorithm;
+    use super::*;
+
+    // 一种比较排序的三个变体。非捕获闭包可以转换成函数指针，因此能放进同一张表
+    struct Variants {
+        name: &'static str,
+        by: fn(&mut [&'static str], &mut dyn FnMut(&&'static str, &&'static str) -> Ordering),
+        by_key: fn(&mut [&'static str], &mut dyn FnMut(&&'static str) -> usize),
+        desc: fn(&mut [i32]),
+    }
+
+    macro_rules! variants {
+        ($($name:literal => $by:ident, $by_key:ident, $desc:ident;)*) => {
+            vec![$(Variants {
+                name: $name,
+                by: |arr, compare| $by(arr, compare),
+                by_key: |arr, key| $by_key(arr, key),
+                desc: $desc,
+            }),*]
+        };
+    }
+
+    fn variants() -> Vec<Variants> {
+        variants![
+            "bubble" => bubble_sort_by, bubble_sort_by_key, bubble_sort_desc;
+            "selection" => selection_sort_by, selection_sort_by_key, selection_sort_desc;
+            "insertion" => insertion_sort_by, insertion_sort_by_key, insertion_sort_desc;
+            "merge" => merge_sort_by, merge_sort_by_key, merge_sort_desc;
+            "quick" => quick_sort_by, quick_sort_by_key, quick_sort_desc;
+            "heap" => heap_sort_by, heap_sort_by_key, heap_sort_desc;
+            "shell" => shell_sort_by, shell_sort_by_key, shell_sort_desc;
+            "tim" => tim_sort_by, tim_sort_by_key, tim_sort_desc;
+        ]
+    }
+
+    // 按长度排序时 "pear"、"kiwi"、"plum"、"date"、"lime" 互相相等，
+    // 稳定排序必须保持它们的输入顺序，不稳定排序只要求长度有序
+    const WORDS: [&str; 10] = ["pear", "fig", "banana", "kiwi", "plum", "date", "lime", "apple", "cherry", "melon"];
+
+    fn assert_sorted_as(actual: &[&str], expected: &[&str], stable: bool, context: &str) {
+        if stable {
+            assert_eq!(actual, expected, "{}", context);
+        } else {
+            let lengths = |words: &[&str]| words.iter().map(|w| w.len()).collect::<Vec<_>>();
+            assert_eq!(lengths(actual), lengths(expected), "{}", context);
+            let mut actual = actual.to_vec();
+            let mut expected = expected.to_vec();
+            actual.sort_unstable();
+            expected.sort_unstable();
+            assert_eq!(actual, expected, "{}", context);
+        }
+    }
+
+    #[test]
+    fn test_by_by_key_and_desc_variants() {
+        let ascending = ["fig", "pear", "kiwi", "plum", "date", "lime", "apple", "melon", "banana", "cherry"];
+        let descending = ["banana", "cherry", "apple", "melon", "pear", "kiwi", "plum", "date", "lime", "fig"];
+        for variant in variants() {
+            let stable = find_algorithm(variant.name).unwrap().stable;
+
+            let mut words = WORDS;
+            (variant.by_key)(&mut words, &mut |w| w.len());
+            assert_sorted_as(&words, &ascending, stable, variant.name);
+
+            let mut words = WORDS;
+            (variant.by)(&mut words, &mut |a, b| b.len().cmp(&a.len()));
+            assert_sorted_as(&words, &descending, stable, variant.name);
+
+            let mut arr = vec![64, 34, 25, 12, 22, 11, 90];
+            (variant.desc)(&mut arr);
+            assert_eq!(arr, vec![90, 64, 34, 25, 22, 12, 11], "{}", variant.name);
+        }
+    }
+}
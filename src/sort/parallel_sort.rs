@@ -0,0 +1,245 @@
+// 测试特性：并行分治排序、作用域线程(crossbeam::scope)、Send/Sync约束
+// 语法要点：crossbeam::scope、split_at_mut、F: Fn + Sync、panic::resume_unwind
+// 功能：提供并行归并排序和并行快速排序，长区间拆分到多个线程，短区间回退到顺序版本
+
+use std::cmp::Ordering;
+use std::thread;
+
+use super::instrument::NoopObserver;
+use super::merge_sort::{merge_halves, merge_sort_by};
+use super::quick_sort::{choose_pivot, depth_limit, introsort, partition, NETWORK_THRESHOLD};
+
+// 并行排序配置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParallelConfig {
+    // 区间长度不超过该值时使用顺序排序
+    pub sequential_threshold: usize,
+    // 最多同时使用的线程数
+    pub max_threads: usize,
+}
+
+impl ParallelConfig {
+    pub fn new(sequential_threshold: usize, max_threads: usize) -> Self {
+        ParallelConfig {
+            sequential_threshold: sequential_threshold.max(1),
+            max_threads: max_threads.max(1),
+        }
+    }
+
+    // 每层递归把工作一分为二，因此允许拆分的层数为 ceil(log2(max_threads))
+    fn split_depth(&self) -> usize {
+        self.max_threads.next_power_of_two().trailing_zeros() as usize
+    }
+}
+
+impl Default for ParallelConfig {
+    fn default() -> Self {
+        let threads = thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+        ParallelConfig::new(4096, threads)
+    }
+}
+
+pub fn par_merge_sort<T>(arr: &mut [T])
+where
+    T: PartialOrd + Send,
+{
+    par_merge_sort_with(arr, ParallelConfig::default());
+}
+
+pub fn par_merge_sort_with<T>(arr: &mut [T], config: ParallelConfig)
+where
+    T: PartialOrd + Send,
+{
+    par_merge_sort_by(arr, config, |a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+}
+
+// 归并排序是稳定的，因此结果与 merge_sort_by 完全一致
+pub fn par_merge_sort_by<T, F>(arr: &mut [T], config: ParallelConfig, compare: F)
+where
+    T: Send,
+    F: Fn(&T, &T) -> Ordering + Sync,
+{
+    par_merge_sort_impl(arr, &config, &compare, config.split_depth());
+}
+
+fn par_merge_sort_impl<T, F>(arr: &mut [T], config: &ParallelConfig, compare: &F, depth: usize)
+where
+    T: Send,
+    F: Fn(&T, &T) -> Ordering + Sync,
+{
+    if depth == 0 || arr.len() <= config.sequential_threshold {
+        merge_sort_by(arr, compare);
+        return;
+    }
+
+    let mid = arr.len() / 2;
+    {
+        let (left, right) = arr.split_at_mut(mid);
+        join(
+            || par_merge_sort_impl(left, config, compare, depth - 1),
+            || par_merge_sort_impl(right, config, compare, depth - 1),
+        );
+    }
+
+    let mut buffer: Vec<T> = Vec::with_capacity(mid);
+    // SAFETY: 缓冲区容量为 mid，足以容纳左半部分
+    unsafe {
+        merge_halves(arr, mid, buffer.as_mut_ptr(), &mut |a: &T, b: &T| compare(a, b), &mut NoopObserver, 0)
+    };
+}
+
+pub fn par_quick_sort<T>(arr: &mut [T])
+where
+    T: PartialOrd + Send,
+{
+    par_quick_sort_with(arr, ParallelConfig::default());
+}
+
+pub fn par_quick_sort_with<T>(arr: &mut [T], config: ParallelConfig)
+where
+    T: PartialOrd + Send,
+{
+    par_quick_sort_by(arr, config, |a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+}
+
+// 与 quick_sort_by 使用相同的主元选择和划分，两侧区间互不重叠，
+// 所以无论线程如何调度，结果都与顺序版本逐元素相同
+pub fn par_quick_sort_by<T, F>(arr: &mut [T], config: ParallelConfig, compare: F)
+where
+    T: Send,
+    F: Fn(&T, &T) -> Ordering + Sync,
+{
+    let limit = depth_limit(arr.len());
+    par_quick_sort_impl(arr, &config, &compare, config.split_depth(), limit);
+}
+
+fn par_quick_sort_impl<T, F>(
+    arr: &mut [T],
+    config: &ParallelConfig,
+    compare: &F,
+    depth: usize,
+    depth_limit: usize,
+) where
+    T: Send,
+    F: Fn(&T, &T) -> Ordering + Sync,
+{
+    // 排序网络阈值和堆排序回退都交给顺序版本处理。阈值低于排序网络阈值时，
+    // 短区间若在这里继续划分，就会与顺序版本走不同的路径，相等元素的顺序随之不同
+    let threshold = config.sequential_threshold.max(NETWORK_THRESHOLD);
+    if depth == 0 || depth_limit == 0 || arr.len() <= threshold {
+        introsort(arr, &mut |a: &T, b: &T| compare(a, b), &mut NoopObserver, 0, depth_limit);
+        return;
+    }
+
+    let mut cmp = |a: &T, b: &T| compare(a, b);
+    let pivot = choose_pivot(arr, &mut cmp, &mut NoopObserver, 0);
+    let (lt, gt) = partition(arr, pivot, &mut cmp, &mut NoopObserver, 0);
+    let (left, rest) = arr.split_at_mut(lt);
+    let right = &mut rest[gt - lt..];
+    join(
+        || par_quick_sort_impl(left, config, compare, depth - 1, depth_limit - 1),
+        || par_quick_sort_impl(right, config, compare, depth - 1, depth_limit - 1),
+    );
+}
+
+// 在新线程中执行 a，当前线程执行 b，两者都完成后返回；子线程的 panic 会原样传播
+fn join<A, B>(a: A, b: B)
+where
+    A: FnOnce() + Send,
+    B: FnOnce() + Send,
+{
+    let result = crossbeam::scope(|s| {
+        s.spawn(|_| a());
+        b();
+    });
+    if let Err(panic) = result {
+        std::panic::resume_unwind(panic);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sort::{merge_sort_by, quick_sort_by};
+    use crate::sort::rng::Rng;
+
+    fn random_vec(len: usize, modulo: u64) -> Vec<u64> {
+        let mut rng = Rng::new(0x9e37_79b9_7f4a_7c15);
+        (0..len).map(|_| rng.below(modulo)).collect()
+    }
+
+    #[test]
+    fn test_par_merge_sort() {
+        let mut arr = vec![64, 34, 25, 12, 22, 11, 90];
+        par_merge_sort(&mut arr);
+        assert_eq!(arr, vec![11, 12, 22, 25, 34, 64, 90]);
+    }
+
+    #[test]
+    fn test_par_quick_sort() {
+        let mut arr = vec![64, 34, 25, 12, 22, 11, 90];
+        par_quick_sort(&mut arr);
+        assert_eq!(arr, vec![11, 12, 22, 25, 34, 64, 90]);
+    }
+
+    #[test]
+    fn test_empty_array() {
+        let mut arr: Vec<i32> = vec![];
+        par_merge_sort(&mut arr);
+        par_quick_sort(&mut arr);
+        assert_eq!(arr, Vec::<i32>::new());
+    }
+
+    #[test]
+    fn test_matches_sequential_merge_sort() {
+        let config = ParallelConfig::new(64, 8);
+        // 只按低位比较，使大量元素相等，以检验稳定性是否与顺序版本一致
+        let by_low_bits = |a: &u64, b: &u64| (a % 16).cmp(&(b % 16));
+        let input = random_vec(20_000, 1_000_000);
+
+        let mut expected = input.clone();
+        merge_sort_by(&mut expected, by_low_bits);
+        let mut actual = input;
+        par_merge_sort_by(&mut actual, config, by_low_bits);
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_matches_sequential_quick_sort() {
+        let config = ParallelConfig::new(64, 8);
+        let by_low_bits = |a: &u64, b: &u64| (a % 16).cmp(&(b % 16));
+        for input in [random_vec(20_000, 1_000_000), random_vec(20_000, 3), (0..20_000).collect()] {
+            let mut expected = input.clone();
+            quick_sort_by(&mut expected, by_low_bits);
+            let mut actual = input;
+            par_quick_sort_by(&mut actual, config, by_low_bits);
+            assert_eq!(actual, expected);
+        }
+    }
+
+    #[test]
+    fn test_matches_sequential_below_network_threshold() {
+        // 阈值小于排序网络阈值(16)时，短区间同样必须交给排序网络；
+        // 用 (键, 原下标) 记录只按键比较，任何路径差异都会改变相等键的顺序
+        let config = ParallelConfig::new(1, 8);
+        let by_key = |a: &(u64, usize), b: &(u64, usize)| a.0.cmp(&b.0);
+        for len in 0..200 {
+            let input: Vec<(u64, usize)> = random_vec(len, 4).into_iter().zip(0..).collect();
+            let mut expected = input.clone();
+            quick_sort_by(&mut expected, by_key);
+            let mut actual = input;
+            par_quick_sort_by(&mut actual, config, by_key);
+            assert_eq!(actual, expected, "len = {}", len);
+        }
+    }
+
+    #[test]
+    fn test_single_thread_config() {
+        let config = ParallelConfig::new(1, 1);
+        let mut arr = random_vec(1000, 100);
+        let mut expected = arr.clone();
+        expected.sort();
+        par_quick_sort_with(&mut arr, config);
+        assert_eq!(arr, expected);
+    }
+}
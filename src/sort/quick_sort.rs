@@ -1,35 +1,213 @@
 // 测试特性：快速排序算法、递归函数、切片分割、分治算法
 // 语法要点：递归调用、切片操作&mut [T]、分区(partition)函数
-// 功能：实现高效的快速排序算法，演示递归和分治思想
+// 功能：实现内省排序(introsort)风格的快速排序：三数/九数取中选主元、三路划分、
+//       小区间改用排序网络、递归过深时退化为堆排序，保证最坏 O(n log n)
+use std::cmp::Ordering;
+
+use super::heap_sort::heap_sort_at;
+use super::instrument::{NoopObserver, SortObserver};
+use super::sort_network::network_sort_at;
+
+// 不超过该长度的区间直接使用排序网络
+pub(crate) const NETWORK_THRESHOLD: usize = 16;
+// 超过该长度时使用九数取中(ninther)选择主元
+const NINTHER_THRESHOLD: usize = 128;
+
 pub fn quick_sort<T: PartialOrd>(arr: &mut [T]) {
-    if arr.len() <= 1 {
-        return;
+    quick_sort_by(arr, |a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+}
+
+pub fn quick_sort_desc<T: PartialOrd>(arr: &mut [T]) {
+    quick_sort_by(arr, |a, b| b.partial_cmp(a).unwrap_or(Ordering::Equal));
+}
+
+pub fn quick_sort_by<T, F>(arr: &mut [T], compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    quick_sort_observed(arr, compare, &mut NoopObserver);
+}
+
+pub fn quick_sort_observed<T, F, O>(arr: &mut [T], mut compare: F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver + ?Sized,
+{
+    let depth_limit = depth_limit(arr.len());
+    introsort(arr, &mut compare, observer, 0, depth_limit);
+}
+
+pub fn quick_sort_by_key<T, K, F>(arr: &mut [T], mut key: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    quick_sort_by(arr, |a, b| key(a).cmp(&key(b)));
+}
+
+// 递归深度上限：2 * (floor(log2(n)) + 1)，即 n 的二进制位数的两倍；n 为 0 时为 0
+pub(crate) fn depth_limit(len: usize) -> usize {
+    2 * (usize::BITS - len.leading_zeros()) as usize
+}
+
+// base 为 arr 在整个切片中的起始下标，仅用于向观察者报告位置
+pub(crate) fn introsort<T, F, O>(
+    mut arr: &mut [T],
+    compare: &mut F,
+    observer: &mut O,
+    mut base: usize,
+    mut depth_limit: usize,
+) where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver + ?Sized,
+{
+    loop {
+        if arr.len() <= NETWORK_THRESHOLD {
+            network_sort_at(arr, compare, observer, base);
+            return;
+        }
+        if depth_limit == 0 {
+            heap_sort_at(arr, compare, observer, base);
+            return;
+        }
+        depth_limit -= 1;
+        
+        let pivot = choose_pivot(arr, compare, observer, base);
+        let (lt, gt) = partition(arr, pivot, compare, observer, base);
+        observer.on_partition(base, base + lt, base + gt, base + arr.len());
+        
+        // 只对较短的一侧递归，较长的一侧继续循环，栈深度保持 O(log n)
+        let (left, rest) = std::mem::take(&mut arr).split_at_mut(lt);
+        let right = &mut rest[gt - lt..];
+        if left.len() < right.len() {
+            introsort(left, compare, observer, base, depth_limit);
+            arr = right;
+            base += gt;
+        } else {
+            introsort(right, compare, observer, base + gt, depth_limit);
+            arr = left;
+        }
     }
-    
-    let pivot = partition(arr);
-    quick_sort(&mut arr[..pivot]);
-    quick_sort(&mut arr[pivot + 1..]);
 }
 
-fn partition<T: PartialOrd>(arr: &mut [T]) -> usize {
+// 选择主元下标：短区间三数取中，长区间九数取中
+pub(crate) fn choose_pivot<T, F, O>(arr: &[T], compare: &mut F, observer: &mut O, base: usize) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver + ?Sized,
+{
     let len = arr.len();
-    let pivot = len - 1;
-    let mut i = 0;
+    if len < 3 {
+        return 0;
+    }
+    let mut median = |a: usize, b: usize, c: usize| median_of_three(arr, a, b, c, compare, observer, base);
+    let (a, b, c) = (0, len / 2, len - 1);
+    if len < NINTHER_THRESHOLD {
+        return median(a, b, c);
+    }
+    let step = len / 8;
+    let m1 = median(a, a + step, a + 2 * step);
+    let m2 = median(b - step, b, b + step);
+    let m3 = median(c - 2 * step, c - step, c);
+    median(m1, m2, m3)
+}
+
+fn median_of_three<T, F, O>(
+    arr: &[T],
+    a: usize,
+    b: usize,
+    c: usize,
+    compare: &mut F,
+    observer: &mut O,
+    base: usize,
+) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver + ?Sized,
+{
+    let mut less = |x: usize, y: usize| {
+        observer.on_compare(base + x, base + y);
+        compare(&arr[x], &arr[y]) == Ordering::Less
+    };
+    let ab = less(a, b);
+    let bc = less(b, c);
+    if ab == bc {
+        return b;
+    }
+    let ac = less(a, c);
+    if ab == ac {
+        c
+    } else {
+        a
+    }
+}
+
+// Dijkstra 三路划分：以 arr[pivot] 为主元，返回等于主元的区间 [lt, gt)
+// 划分后 arr[..lt] < 主元，arr[lt..gt] == 主元，arr[gt..] > 主元
+pub(crate) fn partition<T, F, O>(
+    arr: &mut [T],
+    pivot: usize,
+    compare: &mut F,
+    observer: &mut O,
+    base: usize,
+) -> (usize, usize)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver + ?Sized,
+{
+    let len = arr.len();
+    if len == 0 {
+        return (0, 0);
+    }
+    if pivot != 0 {
+        observer.on_swap(base, base + pivot);
+        arr.swap(0, pivot);
+    }
+    let mut lt = 0;
+    let mut i = 1;
+    let mut gt = len;
     
-    for j in 0..len - 1 {
-        if arr[j] <= arr[pivot] {
-            arr.swap(i, j);
-            i += 1;
+    // arr[lt] 始终是等于主元的元素，用它作为比较基准
+    while i < gt {
+        observer.on_compare(base + i, base + lt);
+        match compare(&arr[i], &arr[lt]) {
+            Ordering::Less => {
+                observer.on_swap(base + lt, base + i);
+                arr.swap(lt, i);
+                lt += 1;
+                i += 1;
+            }
+            Ordering::Greater => {
+                gt -= 1;
+                if i != gt {
+                    observer.on_swap(base + i, base + gt);
+                    arr.swap(i, gt);
+                }
+            }
+            Ordering::Equal => i += 1,
         }
     }
-    
-    arr.swap(i, pivot);
-    i
+    (lt, gt)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::sort::rng::Rng;
+
+    fn is_sorted<T: PartialOrd>(arr: &[T]) -> bool {
+        arr.windows(2).all(|w| w[0] <= w[1])
+    }
+
+    // 统计比较次数，用于验证有序输入不会退化为 O(n²)
+    fn count_comparisons(arr: &mut [u32]) -> usize {
+        let mut count = 0;
+        quick_sort_by(arr, |a, b| {
+            count += 1;
+            a.cmp(b)
+        });
+        count
+    }
 
     #[test]
     fn test_quick_sort() {
@@ -51,4 +229,57 @@ mod tests {
         quick_sort(&mut arr);
         assert_eq!(arr, vec![1]);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_adversarial_inputs_stay_n_log_n() {
+        let n = 100_000u32;
+        let limit = 4 * n as usize * depth_limit(n as usize);
+        let inputs: Vec<(&str, Vec<u32>)> = vec![
+            ("sorted", (0..n).collect()),
+            ("reversed", (0..n).rev().collect()),
+            ("all equal", vec![7; n as usize]),
+            ("organ pipe", (0..n / 2).chain((0..n / 2).rev()).collect()),
+            ("sawtooth", (0..n).map(|x| x % 100).collect()),
+        ];
+        for (name, mut arr) in inputs {
+            let comparisons = count_comparisons(&mut arr);
+            assert!(is_sorted(&arr), "{}", name);
+            assert!(comparisons < limit, "{}: {} comparisons", name, comparisons);
+        }
+    }
+
+    #[test]
+    fn test_random_and_duplicate_heavy() {
+        let mut rng = Rng::new(0x2545_f491);
+        for len in [0, 1, 2, 3, 15, 16, 17, 127, 128, 129, 1000, 5000] {
+            let mut arr: Vec<u32> = (0..len).map(|_| rng.next_u32()).collect();
+            let mut expected = arr.clone();
+            expected.sort();
+            quick_sort(&mut arr);
+            assert_eq!(arr, expected);
+
+            let mut dups: Vec<u32> = (0..len).map(|_| rng.below(4) as u32).collect();
+            let mut expected = dups.clone();
+            expected.sort();
+            quick_sort(&mut dups);
+            assert_eq!(dups, expected);
+        }
+    }
+
+    #[test]
+    fn test_depth_limit_falls_back_to_heap_sort() {
+        let mut arr: Vec<i32> = (0..1000).rev().collect();
+        introsort(&mut arr, &mut |a: &i32, b: &i32| a.cmp(b), &mut NoopObserver, 0, 0);
+        assert!(is_sorted(&arr));
+    }
+
+    #[test]
+    fn test_three_way_partition() {
+        let mut arr = vec![5, 1, 5, 9, 5, 3, 7, 5];
+        let (lt, gt) = partition(&mut arr, 0, &mut |a: &i32, b: &i32| a.cmp(b), &mut NoopObserver, 0);
+        assert!(arr[..lt].iter().all(|&x| x < 5));
+        assert!(arr[lt..gt].iter().all(|&x| x == 5));
+        assert!(arr[gt..].iter().all(|&x| x > 5));
+        assert_eq!(gt - lt, 4);
+    }
+}
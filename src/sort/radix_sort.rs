@@ -1,42 +1,199 @@
-pub fn radix_sort(arr: &mut [u32]) {
-    if arr.is_empty() {
+// 测试特性：基数排序、Trait关联常量、声明宏批量实现Trait、位运算
+// 语法要点：trait RadixKey { const BYTES }、macro_rules!、符号位翻转、AsRef<[u8]>
+// 功能：实现以 256 为基数的 LSD 基数排序(支持所有整数宽度及有符号整数)，
+//       按键排序记录，以及面向字节串/字符串的 MSD 基数排序(American flag sort)
+use super::insertion_sort::insertion_sort_by;
+use super::instrument::{NoopObserver, SortObserver};
+
+// 可以按字节拆分的整数键。radix_bits 返回的无符号值与键的大小顺序一致
+pub trait RadixKey: Copy {
+    const BYTES: usize;
+
+    fn radix_bits(self) -> u128;
+}
+
+macro_rules! impl_radix_key_unsigned {
+    ($($t:ty),*) => {
+        $(
+            impl RadixKey for $t {
+                const BYTES: usize = std::mem::size_of::<$t>();
+
+                fn radix_bits(self) -> u128 {
+                    self as u128
+                }
+            }
+        )*
+    };
+}
+
+// 有符号整数翻转符号位后，按无符号比较即可得到正确顺序
+macro_rules! impl_radix_key_signed {
+    ($($t:ty => $u:ty),*) => {
+        $(
+            impl RadixKey for $t {
+                const BYTES: usize = std::mem::size_of::<$t>();
+
+                fn radix_bits(self) -> u128 {
+                    ((self as $u) ^ (1 << (<$u>::BITS - 1))) as u128
+                }
+            }
+        )*
+    };
+}
+
+impl_radix_key_unsigned!(u8, u16, u32, u64, u128, usize);
+impl_radix_key_signed!(i8 => u8, i16 => u16, i32 => u32, i64 => u64, i128 => u128, isize => usize);
+
+pub fn radix_sort<K: RadixKey>(arr: &mut [K]) {
+    radix_sort_observed(arr, &mut NoopObserver);
+}
+
+pub fn radix_sort_observed<K, O>(arr: &mut [K], observer: &mut O)
+where
+    K: RadixKey,
+    O: SortObserver + ?Sized,
+{
+    lsd_passes(arr, K::BYTES, |k| k.radix_bits(), observer);
+}
+
+// 按整数键稳定地排序记录，元素本身不需要 Clone
+pub fn radix_sort_by_key<T, K, F>(arr: &mut [T], mut key: F)
+where
+    K: RadixKey,
+    F: FnMut(&T) -> K,
+{
+    if arr.len() <= 1 {
         return;
     }
-    
-    let max_val = *arr.iter().max().unwrap();
-    let mut exp = 1;
-    
-    while max_val / exp > 0 {
-        counting_sort_for_radix(arr, exp);
-        exp *= 10;
-    }
+    let mut keyed: Vec<(K, usize)> = arr.iter().map(&mut key).zip(0..).collect();
+    lsd_passes(&mut keyed, K::BYTES, |&(k, _)| k.radix_bits(), &mut NoopObserver);
+    let mut order: Vec<usize> = keyed.into_iter().map(|(_, index)| index).collect();
+    apply_permutation(arr, &mut order, &mut NoopObserver);
 }
 
-fn counting_sort_for_radix(arr: &mut [u32], exp: u32) {
+// 每轮按一个字节做稳定的计数排序，从最低字节到最高字节。
+// 每轮先算出每个元素的目标位置，再沿置换环原地交换，不需要输出数组
+fn lsd_passes<T, D, O>(arr: &mut [T], bytes: usize, bits: D, observer: &mut O)
+where
+    D: Fn(&T) -> u128,
+    O: SortObserver + ?Sized,
+{
     let n = arr.len();
-    let mut output = vec![0u32; n];
-    let mut count = [0usize; 10];
-    
-    for &item in arr.iter() {
-        count[((item / exp) % 10) as usize] += 1;
+    if n <= 1 {
+        return;
     }
+    observer.on_alloc(n);
+    let mut order = vec![0usize; n];
     
-    for i in 1..10 {
-        count[i] += count[i - 1];
+    for pass in 0..bytes {
+        let shift = pass * 8;
+        let digit = |item: &T| ((bits(item) >> shift) & 0xff) as usize;
+        
+        let mut count = [0usize; 256];
+        for item in arr.iter() {
+            count[digit(item)] += 1;
+        }
+        // 所有元素这一字节都相同时跳过本轮
+        if count.contains(&n) {
+            continue;
+        }
+        
+        let mut offset = 0;
+        for c in count.iter_mut() {
+            let bucket_len = *c;
+            *c = offset;
+            offset += bucket_len;
+        }
+        for (index, item) in arr.iter().enumerate() {
+            let d = digit(item);
+            order[count[d]] = index;
+            count[d] += 1;
+        }
+        apply_permutation(arr, &mut order, observer);
     }
-    
-    for &item in arr.iter().rev() {
-        let digit = ((item / exp) % 10) as usize;
-        count[digit] -= 1;
-        output[count[digit]] = item;
+}
+
+// 按 order 重排 arr：排序后 arr[i] 为原来的 arr[order[i]]。沿置换环交换元素，只需 O(n) 次 swap。
+// 结束后 order 被重置为恒等置换
+pub(crate) fn apply_permutation<T, O>(arr: &mut [T], order: &mut [usize], observer: &mut O)
+where
+    O: SortObserver + ?Sized,
+{
+    for i in 0..arr.len() {
+        let mut j = i;
+        while order[j] != i {
+            let k = order[j];
+            observer.on_swap(j, k);
+            arr.swap(j, k);
+            order[j] = j;
+            j = k;
+        }
+        order[j] = j;
+    }
+}
+
+// 不超过该长度的桶改用插入排序
+const MSD_INSERTION_THRESHOLD: usize = 32;
+
+// MSD 基数排序，适用于 String、&str、Vec<u8>、&[u8] 等字节串，按字典序排序
+pub fn msd_radix_sort<S: AsRef<[u8]>>(arr: &mut [S]) {
+    // 待处理的区间 (start, end, depth)：区间内的串前 depth 个字节都相同。
+    // 用显式栈代替递归：共享前缀很长时递归深度等于前缀长度，每层还要占用约 8 KB 的桶数组
+    let mut pending = vec![(0, arr.len(), 0)];
+    let mut count = [0usize; 257];
+    let mut starts = [0usize; 257];
+    let mut ends = [0usize; 257];
+    while let Some((start, end, depth)) = pending.pop() {
+        let arr = &mut arr[start..end];
+        if arr.len() <= MSD_INSERTION_THRESHOLD {
+            insertion_sort_by(arr, |a, b| a.as_ref()[depth..].cmp(&b.as_ref()[depth..]));
+            continue;
+        }
+        
+        // 桶 0 存放长度恰好为 depth 的串，桶 b + 1 存放该位置字节为 b 的串
+        let bucket_of = |s: &S| s.as_ref().get(depth).map_or(0, |&b| b as usize + 1);
+        count.fill(0);
+        for s in arr.iter() {
+            count[bucket_of(s)] += 1;
+        }
+        let mut offset = 0;
+        for bucket in 0..257 {
+            starts[bucket] = offset;
+            offset += count[bucket];
+            ends[bucket] = offset;
+        }
+        
+        // American flag sort：把每个元素直接交换到它所属的桶中
+        let mut next = starts;
+        for bucket in 0..257 {
+            while next[bucket] < ends[bucket] {
+                let target = bucket_of(&arr[next[bucket]]);
+                if target == bucket {
+                    next[bucket] += 1;
+                } else {
+                    arr.swap(next[bucket], next[target]);
+                    next[target] += 1;
+                }
+            }
+        }
+        
+        for bucket in 1..257 {
+            if ends[bucket] - starts[bucket] > 1 {
+                pending.push((start + starts[bucket], start + ends[bucket], depth + 1));
+            }
+        }
     }
-    
-    arr.copy_from_slice(&output);
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::sort::rng::Rng;
+
+    fn random_u64s(len: usize) -> Vec<u64> {
+        let mut rng = Rng::new(0x0dd_b1a5_e5bad_5eed);
+        (0..len).map(|_| rng.next_u64()).collect()
+    }
 
     #[test]
     fn test_radix_sort() {
@@ -49,7 +206,7 @@ mod tests {
     fn test_empty_array() {
         let mut arr: Vec<u32> = vec![];
         radix_sort(&mut arr);
-        assert_eq!(arr, Vec::<i32>::new());
+        assert_eq!(arr, Vec::<u32>::new());
     }
 
     #[test]
@@ -58,4 +215,106 @@ mod tests {
         radix_sort(&mut arr);
         assert_eq!(arr, vec![1]);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_all_integer_widths() {
+        let mut bytes = vec![200u8, 3, 255, 0, 17];
+        radix_sort(&mut bytes);
+        assert_eq!(bytes, vec![0, 3, 17, 200, 255]);
+
+        let mut wide = vec![u128::MAX, 1 << 100, 5, 0, 1 << 64];
+        radix_sort(&mut wide);
+        assert_eq!(wide, vec![0, 5, 1 << 64, 1 << 100, u128::MAX]);
+
+        let mut randoms = random_u64s(5000);
+        let mut expected = randoms.clone();
+        expected.sort();
+        radix_sort(&mut randoms);
+        assert_eq!(randoms, expected);
+    }
+
+    #[test]
+    fn test_signed_integers() {
+        let mut small = vec![i8::MAX, -1, 0, i8::MIN, 5, -100];
+        radix_sort(&mut small);
+        assert_eq!(small, vec![i8::MIN, -100, -1, 0, 5, i8::MAX]);
+
+        let mut signed: Vec<i64> = random_u64s(5000).into_iter().map(|x| x as i64).collect();
+        let mut expected = signed.clone();
+        expected.sort();
+        radix_sort(&mut signed);
+        assert_eq!(signed, expected);
+
+        let mut huge = vec![i128::MAX, i128::MIN, -1, 1];
+        radix_sort(&mut huge);
+        assert_eq!(huge, vec![i128::MIN, -1, 1, i128::MAX]);
+    }
+
+    #[test]
+    fn test_radix_sort_by_key_is_stable() {
+        // 不实现 Clone 的记录
+        #[derive(Debug, PartialEq)]
+        struct Row {
+            id: i32,
+            name: &'static str,
+        }
+        let mut rows = vec![
+            Row { id: 3, name: "c1" },
+            Row { id: -7, name: "a" },
+            Row { id: 3, name: "c2" },
+            Row { id: 0, name: "b" },
+            Row { id: 3, name: "c3" },
+        ];
+        radix_sort_by_key(&mut rows, |r| r.id);
+        let names: Vec<_> = rows.iter().map(|r| r.name).collect();
+        assert_eq!(names, vec!["a", "b", "c1", "c2", "c3"]);
+    }
+
+    #[test]
+    fn test_msd_radix_sort_strings() {
+        let mut words: Vec<String> = ["banana", "", "apple", "app", "b", "apple", "ápple", "zoo"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let mut expected = words.clone();
+        expected.sort();
+        msd_radix_sort(&mut words);
+        assert_eq!(words, expected);
+
+        let mut slices: Vec<&[u8]> = vec![b"\xff", b"\x00\x01", b"\x00", b""];
+        msd_radix_sort(&mut slices);
+        assert_eq!(slices, vec![&b""[..], b"\x00", b"\x00\x01", b"\xff"]);
+    }
+
+    #[test]
+    fn test_msd_radix_sort_large() {
+        let mut words: Vec<String> = random_u64s(3000)
+            .into_iter()
+            .map(|x| format!("{:x}", x % 100_000))
+            .collect();
+        let mut expected = words.clone();
+        expected.sort();
+        msd_radix_sort(&mut words);
+        assert_eq!(words, expected);
+    }
+
+    #[test]
+    fn test_msd_radix_sort_long_shared_prefix() {
+        // 每多一个共享字节就多处理一层桶，前缀很长时不能依赖递归
+        let prefix = "/".repeat(50_000);
+        let mut paths: Vec<String> = random_u64s(40).into_iter().map(|x| format!("{}{}", prefix, x % 7)).collect();
+        paths.push(prefix.clone());
+        paths.extend(paths.clone());
+        let mut expected = paths.clone();
+        expected.sort();
+        msd_radix_sort(&mut paths);
+        assert_eq!(paths, expected);
+    }
+
+    #[test]
+    fn test_apply_permutation() {
+        let mut arr = vec!['a', 'b', 'c', 'd', 'e'];
+        apply_permutation(&mut arr, &mut [4, 2, 0, 3, 1], &mut NoopObserver);
+        assert_eq!(arr, vec!['e', 'c', 'a', 'd', 'b']);
+    }
+}
@@ -0,0 +1,77 @@
+// 测试特性：伪随机数生成器、位运算、可复现的随机序列
+// 语法要点：wrapping_add/wrapping_mul、移位异或、u128乘法取高位
+// 功能：不依赖外部 crate 的 SplitMix64 生成器，相同种子总是产生相同序列，
+//       供基准测试和随机化测试生成输入数据
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    // [0, bound) 内的均匀整数，bound 为 0 时返回 0
+    pub fn below(&mut self, bound: u64) -> u64 {
+        ((self.next_u64() as u128 * bound as u128) >> 64) as u64
+    }
+
+    // [0, 1) 内的均匀浮点数
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    // Fisher-Yates 洗牌
+    pub fn shuffle<T>(&mut self, arr: &mut [T]) {
+        for i in (1..arr.len()).rev() {
+            let j = self.below(i as u64 + 1) as usize;
+            arr.swap(i, j);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_seed_same_sequence() {
+        let mut a = Rng::new(42);
+        let mut b = Rng::new(42);
+        let first: Vec<u64> = (0..5).map(|_| a.next_u64()).collect();
+        let second: Vec<u64> = (0..5).map(|_| b.next_u64()).collect();
+        assert_eq!(first, second);
+        assert_ne!(Rng::new(1).next_u64(), Rng::new(2).next_u64());
+    }
+
+    #[test]
+    fn test_ranges() {
+        let mut rng = Rng::new(7);
+        for _ in 0..1000 {
+            assert!(rng.below(10) < 10);
+            let x = rng.next_f64();
+            assert!((0.0..1.0).contains(&x));
+        }
+        assert_eq!(rng.below(0), 0);
+
+        let mut arr: Vec<u32> = (0..100).collect();
+        rng.shuffle(&mut arr);
+        assert_ne!(arr, (0..100).collect::<Vec<u32>>());
+        arr.sort();
+        assert_eq!(arr, (0..100).collect::<Vec<u32>>());
+    }
+}
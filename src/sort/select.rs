@@ -0,0 +1,238 @@
+// 测试特性：选择算法、快速选择(quickselect)、内省选择(introselect)、有界堆
+// 语法要点：复用 quick_sort::partition 与 heap_sort::heapify、IntoIterator 泛型参数、返回 &mut T
+// 功能：nth_element 把第 k 小的元素放到位置 k，partial_sort 只排好最小的 k 个元素，
+//       top_k 用大小为 k 的堆从任意迭代器中取出最小的 k 个元素，都支持自定义比较函数
+
+use std::cmp::Ordering;
+
+use super::heap_sort::{heap_sort_at, heapify};
+use super::instrument::NoopObserver;
+use super::quick_sort::{choose_pivot, depth_limit, introsort, partition};
+
+// 重新排列 arr，使 arr[k] 为排序后位于 k 的元素，
+// 且 arr[..k] 中的元素都不大于它、arr[k + 1..] 中的元素都不小于它。
+// k 越界时 panic
+pub fn nth_element<T: PartialOrd>(arr: &mut [T], k: usize) -> &mut T {
+    nth_element_by(arr, k, |a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal))
+}
+
+pub fn nth_element_by<T, F>(arr: &mut [T], k: usize, mut compare: F) -> &mut T
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    assert!(k < arr.len(), "下标 {} 越界，长度为 {}", k, arr.len());
+    let limit = depth_limit(arr.len());
+    introselect(arr, k, &mut compare, limit);
+    &mut arr[k]
+}
+
+pub fn nth_element_by_key<T, K, F>(arr: &mut [T], k: usize, mut key: F) -> &mut T
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    nth_element_by(arr, k, |a, b| key(a).cmp(&key(b)))
+}
+
+// 快速选择：每轮划分后只在包含 k 的一侧继续。
+// 划分次数超过 depth_limit 时说明主元持续很差，改用堆排序剩余区间，保证最坏 O(n log n)
+fn introselect<T, F>(mut arr: &mut [T], mut k: usize, compare: &mut F, mut depth_limit: usize)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    loop {
+        if arr.len() <= 1 {
+            return;
+        }
+        if depth_limit == 0 {
+            heap_sort_at(arr, compare, &mut NoopObserver, 0);
+            return;
+        }
+        depth_limit -= 1;
+
+        let pivot = choose_pivot(arr, compare, &mut NoopObserver, 0);
+        let (lt, gt) = partition(arr, pivot, compare, &mut NoopObserver, 0);
+        if k < lt {
+            arr = &mut arr[..lt];
+        } else if k < gt {
+            // k 落在等于主元的区间内，已经就位
+            return;
+        } else {
+            arr = &mut arr[gt..];
+            k -= gt;
+        }
+    }
+}
+
+// 把最小的 k 个元素按顺序放到 arr[..k]，其余元素的顺序不确定。
+// k 大于长度时相当于完整排序
+pub fn partial_sort<T: PartialOrd>(arr: &mut [T], k: usize) {
+    partial_sort_by(arr, k, |a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+}
+
+pub fn partial_sort_by<T, F>(arr: &mut [T], k: usize, mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let k = k.min(arr.len());
+    if k == 0 {
+        return;
+    }
+    // 先选出第 k 小的元素，再只排序它前面的部分：O(n + k log k)
+    if k < arr.len() {
+        let limit = depth_limit(arr.len());
+        introselect(arr, k - 1, &mut compare, limit);
+    }
+    let head = &mut arr[..k];
+    let limit = depth_limit(head.len());
+    introsort(head, &mut compare, &mut NoopObserver, 0, limit);
+}
+
+pub fn partial_sort_by_key<T, K, F>(arr: &mut [T], k: usize, mut key: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    partial_sort_by(arr, k, |a, b| key(a).cmp(&key(b)));
+}
+
+// 从迭代器中取出最小的 k 个元素，按升序返回。
+// 只保存 k 个元素，适合数据流；需要最大的 k 个时传入反向的比较函数
+pub fn top_k<T, I>(items: I, k: usize) -> Vec<T>
+where
+    T: PartialOrd,
+    I: IntoIterator<Item = T>,
+{
+    top_k_by(items, k, |a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal))
+}
+
+// 用大顶堆保存当前最小的 k 个元素，新元素小于堆顶时替换堆顶并下沉
+pub fn top_k_by<T, I, F>(items: I, k: usize, mut compare: F) -> Vec<T>
+where
+    I: IntoIterator<Item = T>,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    if k == 0 {
+        return Vec::new();
+    }
+    let mut items = items.into_iter();
+    let mut heap: Vec<T> = items.by_ref().take(k).collect();
+    let n = heap.len();
+    for i in (0..n / 2).rev() {
+        heapify(&mut heap, n, i, &mut compare, &mut NoopObserver, 0);
+    }
+    for item in items {
+        if compare(&item, &heap[0]) == Ordering::Less {
+            heap[0] = item;
+            heapify(&mut heap, n, 0, &mut compare, &mut NoopObserver, 0);
+        }
+    }
+    heap_sort_at(&mut heap, &mut compare, &mut NoopObserver, 0);
+    heap
+}
+
+pub fn top_k_by_key<T, I, K, F>(items: I, k: usize, mut key: F) -> Vec<T>
+where
+    I: IntoIterator<Item = T>,
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    top_k_by(items, k, |a, b| key(a).cmp(&key(b)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sort::rng::Rng;
+
+    fn inputs() -> Vec<Vec<u32>> {
+        let mut rng = Rng::new(31);
+        vec![
+            (0..500).map(|_| rng.next_u32()).collect(),
+            (0..500).map(|_| rng.below(5) as u32).collect(),
+            (0..500).collect(),
+            (0..500).rev().collect(),
+            (0..250).chain((0..250).rev()).collect(),
+            vec![7; 300],
+            vec![42],
+        ]
+    }
+
+    #[test]
+    fn test_nth_element() {
+        for input in inputs() {
+            let mut sorted = input.clone();
+            sorted.sort();
+            for k in [0, input.len() / 3, input.len() / 2, input.len() - 1] {
+                let mut arr = input.clone();
+                assert_eq!(*nth_element(&mut arr, k), sorted[k]);
+                assert!(arr[..k].iter().all(|&x| x <= arr[k]));
+                assert!(arr[k + 1..].iter().all(|&x| x >= arr[k]));
+            }
+        }
+    }
+
+    #[test]
+    fn test_median_with_comparator() {
+        let mut words = vec!["pear", "fig", "banana", "kiwi", "apple"];
+        // 长度依次为 3, 4, 4, 5, 6，选择不稳定，中位数可能是 pear 或 kiwi
+        assert_eq!(nth_element_by_key(&mut words, 2, |w| w.len()).len(), 4);
+        let mut arr = vec![3, 1, 4, 1, 5, 9, 2, 6];
+        // 降序比较时位置 0 是最大值
+        assert_eq!(*nth_element_by(&mut arr, 0, |a, b| b.cmp(a)), 9);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_nth_element_out_of_bounds() {
+        nth_element(&mut [1, 2, 3], 3);
+    }
+
+    #[test]
+    fn test_introselect_falls_back_to_heap_sort() {
+        let mut arr: Vec<i32> = (0..1000).rev().collect();
+        introselect(&mut arr, 10, &mut |a: &i32, b: &i32| a.cmp(b), 0);
+        assert_eq!(arr[10], 10);
+    }
+
+    #[test]
+    fn test_partial_sort() {
+        for input in inputs() {
+            let mut sorted = input.clone();
+            sorted.sort();
+            for k in [0, 1, 10, input.len(), input.len() + 5] {
+                let mut arr = input.clone();
+                partial_sort(&mut arr, k);
+                let k = k.min(input.len());
+                assert_eq!(arr[..k], sorted[..k]);
+                let mut rest = arr[k..].to_vec();
+                rest.sort();
+                assert_eq!(rest, sorted[k..]);
+            }
+        }
+
+        let mut words = vec!["pear", "fig", "banana", "kiwi"];
+        partial_sort_by_key(&mut words, 2, |w| w.len());
+        assert_eq!(words[0], "fig");
+        assert_eq!(words[1].len(), 4);
+    }
+
+    #[test]
+    fn test_top_k() {
+        for input in inputs() {
+            let mut sorted = input.clone();
+            sorted.sort();
+            for k in [0, 1, 7, input.len(), input.len() + 1] {
+                let expected = sorted[..k.min(input.len())].to_vec();
+                assert_eq!(top_k(input.iter().copied(), k), expected);
+            }
+        }
+
+        // 任意迭代器，反向比较取最大的 3 个
+        let largest = top_k_by((1..=1_000_000u64).map(|x| x * 7919 % 1_000_003), 3, |a, b| b.cmp(a));
+        assert_eq!(largest, vec![1_000_002, 1_000_001, 1_000_000]);
+
+        let shortest = top_k_by_key(vec!["pear", "fig", "banana", "kiwi"], 1, |w| w.len());
+        assert_eq!(shortest, vec!["fig"]);
+    }
+}
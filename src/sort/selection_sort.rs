@@ -1,18 +1,51 @@
+use std::cmp::Ordering;
+
+use super::instrument::{NoopObserver, SortObserver};
+
 pub fn selection_sort<T: PartialOrd>(arr: &mut [T]) {
+    selection_sort_by(arr, |a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+}
+
+pub fn selection_sort_desc<T: PartialOrd>(arr: &mut [T]) {
+    selection_sort_by(arr, |a, b| b.partial_cmp(a).unwrap_or(Ordering::Equal));
+}
+
+pub fn selection_sort_by<T, F>(arr: &mut [T], compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    selection_sort_observed(arr, compare, &mut NoopObserver);
+}
+
+pub fn selection_sort_observed<T, F, O>(arr: &mut [T], mut compare: F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver + ?Sized,
+{
     let n = arr.len();
     for i in 0..n {
         let mut min_idx = i;
         for j in i + 1..n {
-            if arr[j] < arr[min_idx] {
+            observer.on_compare(j, min_idx);
+            if compare(&arr[j], &arr[min_idx]) == Ordering::Less {
                 min_idx = j;
             }
         }
         if min_idx != i {
+            observer.on_swap(i, min_idx);
             arr.swap(i, min_idx);
         }
     }
 }
 
+pub fn selection_sort_by_key<T, K, F>(arr: &mut [T], mut key: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    selection_sort_by(arr, |a, b| key(a).cmp(&key(b)));
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -37,4 +70,4 @@ mod tests {
         selection_sort(&mut arr);
         assert_eq!(arr, vec![1]);
     }
-}
\ No newline at end of file
+}
@@ -1,26 +1,189 @@
-pub fn shell_sort<T: PartialOrd + Clone>(arr: &mut [T]) {
+// 测试特性：希尔排序、带数据的枚举、间隔序列生成、迭代器适配器
+// 语法要点：enum GapSequence { Custom(Vec<usize>) }、take_while、rev、比较计数闭包
+// 功能：实现可选择间隔序列(Shell、Knuth、Sedgewick、Ciura、Tokuda、自定义)的希尔排序
+use std::cmp::Ordering;
+
+use super::instrument::{NoopObserver, SortObserver};
+
+// 希尔排序的间隔序列
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum GapSequence {
+    // n/2, n/4, ..., 1 (Shell, 1959)
+    #[default]
+    Shell,
+    // (3^k - 1) / 2：1, 4, 13, 40, ... (Knuth, 1973)
+    Knuth,
+    // 4^k + 3·2^(k-1) + 1：1, 8, 23, 77, 281, ... (Sedgewick, 1986)
+    Sedgewick,
+    // 1, 4, 10, 23, 57, 132, 301, 701, 1750，之后按 2.25 倍扩展 (Ciura, 2001)
+    Ciura,
+    // ceil((9·(9/4)^k - 4) / 5)：1, 4, 9, 20, 46, ... (Tokuda, 1992)
+    Tokuda,
+    // 自定义间隔，顺序任意；缺少 1 时会自动补上以保证结果有序
+    Custom(Vec<usize>),
+}
+
+const CIURA_GAPS: [usize; 9] = [1, 4, 10, 23, 57, 132, 301, 701, 1750];
+
+impl GapSequence {
+    // 长度为 n 时实际使用的间隔，按从大到小排列，最后一个为 1
+    pub fn gaps(&self, n: usize) -> Vec<usize> {
+        if n <= 1 {
+            return Vec::new();
+        }
+        let mut gaps: Vec<usize> = match self {
+            GapSequence::Shell => {
+                let mut gaps = Vec::new();
+                let mut gap = n / 2;
+                while gap > 0 {
+                    gaps.push(gap);
+                    gap /= 2;
+                }
+                gaps.reverse();
+                gaps
+            }
+            GapSequence::Knuth => increasing(n, 1, |h| h * 3 + 1),
+            GapSequence::Sedgewick => {
+                let mut gaps = vec![1];
+                for k in 1.. {
+                    let gap = (1usize << (2 * k)) + 3 * (1usize << (k - 1)) + 1;
+                    if gap >= n {
+                        break;
+                    }
+                    gaps.push(gap);
+                }
+                gaps
+            }
+            GapSequence::Ciura => {
+                let mut gaps: Vec<usize> = CIURA_GAPS.iter().copied().take_while(|&g| g < n).collect();
+                if gaps.len() == CIURA_GAPS.len() {
+                    gaps.extend(increasing(n, CIURA_GAPS[8] * 9 / 4, |h| h * 9 / 4));
+                }
+                gaps
+            }
+            GapSequence::Tokuda => {
+                let mut gaps = Vec::new();
+                for k in 0.. {
+                    let gap = ((9.0 * 2.25f64.powi(k) - 4.0) / 5.0).ceil() as usize;
+                    if gap >= n {
+                        break;
+                    }
+                    gaps.push(gap);
+                }
+                gaps
+            }
+            GapSequence::Custom(custom) => {
+                let mut gaps: Vec<usize> = custom.iter().copied().filter(|&g| g > 0 && g < n).collect();
+                gaps.push(1);
+                gaps.sort_unstable();
+                gaps.dedup();
+                gaps
+            }
+        };
+        gaps.reverse();
+        gaps
+    }
+}
+
+// 从 first 开始按 next 递推，收集所有小于 n 的间隔
+fn increasing(n: usize, first: usize, next: impl Fn(usize) -> usize) -> Vec<usize> {
+    let mut gaps = Vec::new();
+    let mut gap = first;
+    while gap < n {
+        gaps.push(gap);
+        gap = next(gap);
+    }
+    gaps
+}
+
+pub fn shell_sort<T: PartialOrd>(arr: &mut [T]) {
+    shell_sort_by(arr, |a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+}
+
+pub fn shell_sort_desc<T: PartialOrd>(arr: &mut [T]) {
+    shell_sort_by(arr, |a, b| b.partial_cmp(a).unwrap_or(Ordering::Equal));
+}
+
+pub fn shell_sort_by<T, F>(arr: &mut [T], compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    shell_sort_with_by(arr, &GapSequence::Shell, compare);
+}
+
+pub fn shell_sort_by_key<T, K, F>(arr: &mut [T], mut key: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    shell_sort_by(arr, |a, b| key(a).cmp(&key(b)));
+}
+
+pub fn shell_sort_with<T: PartialOrd>(arr: &mut [T], sequence: &GapSequence) {
+    shell_sort_with_by(arr, sequence, |a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+}
+
+pub fn shell_sort_with_by<T, F>(arr: &mut [T], sequence: &GapSequence, compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    shell_sort_observed(arr, sequence, compare, &mut NoopObserver);
+}
+
+// 带间隔的插入排序，用相邻间隔的交换代替 clone 后移
+pub fn shell_sort_observed<T, F, O>(arr: &mut [T], sequence: &GapSequence, mut compare: F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver + ?Sized,
+{
     let n = arr.len();
-    let mut gap = n / 2;
     
-    while gap > 0 {
+    for gap in sequence.gaps(n) {
         for i in gap..n {
-            let temp = arr[i].clone();
             let mut j = i;
             
-            while j >= gap && arr[j - gap] > temp {
-                arr[j] = arr[j - gap].clone();
+            while j >= gap {
+                observer.on_compare(j - gap, j);
+                if compare(&arr[j - gap], &arr[j]) != Ordering::Greater {
+                    break;
+                }
+                observer.on_swap(j - gap, j);
+                arr.swap(j - gap, j);
                 j -= gap;
             }
-            
-            arr[j] = temp;
         }
-        gap /= 2;
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::sort::rng::Rng;
+
+    fn all_sequences() -> Vec<GapSequence> {
+        vec![
+            GapSequence::Shell,
+            GapSequence::Knuth,
+            GapSequence::Sedgewick,
+            GapSequence::Ciura,
+            GapSequence::Tokuda,
+            GapSequence::Custom(vec![7, 3]),
+        ]
+    }
+
+    fn random_vec(len: usize) -> Vec<u32> {
+        let mut rng = Rng::new(0xace1);
+        (0..len).map(|_| rng.next_u32()).collect()
+    }
+
+    fn count_comparisons(arr: &mut [u32], sequence: &GapSequence) -> usize {
+        let mut count = 0;
+        shell_sort_with_by(arr, sequence, |a, b| {
+            count += 1;
+            a.cmp(b)
+        });
+        count
+    }
 
     #[test]
     fn test_shell_sort() {
@@ -42,4 +205,52 @@ mod tests {
         shell_sort(&mut arr);
         assert_eq!(arr, vec![1]);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_gap_sequences() {
+        assert_eq!(GapSequence::Shell.gaps(20), vec![10, 5, 2, 1]);
+        assert_eq!(GapSequence::Knuth.gaps(100), vec![40, 13, 4, 1]);
+        assert_eq!(GapSequence::Sedgewick.gaps(300), vec![281, 77, 23, 8, 1]);
+        assert_eq!(GapSequence::Ciura.gaps(60), vec![57, 23, 10, 4, 1]);
+        assert_eq!(GapSequence::Ciura.gaps(5000), vec![3937, 1750, 701, 301, 132, 57, 23, 10, 4, 1]);
+        assert_eq!(GapSequence::Tokuda.gaps(50), vec![46, 20, 9, 4, 1]);
+        assert_eq!(GapSequence::Custom(vec![5, 0, 2, 5, 99]).gaps(10), vec![5, 2, 1]);
+        assert!(GapSequence::Knuth.gaps(1).is_empty());
+    }
+
+    #[test]
+    fn test_every_sequence_sorts() {
+        for sequence in all_sequences() {
+            for len in [0, 1, 2, 10, 100, 1000] {
+                let mut arr = random_vec(len);
+                let mut expected = arr.clone();
+                expected.sort();
+                shell_sort_with(&mut arr, &sequence);
+                assert_eq!(arr, expected, "{:?} len {}", sequence, len);
+            }
+        }
+    }
+
+    // 比较次数基准：运行 `cargo test comparison_counts -- --nocapture` 查看表格
+    #[test]
+    fn test_comparison_counts_per_sequence() {
+        let sizes = [64, 256, 1024, 4096];
+        println!("{:<12}{}", "sequence", sizes.map(|n| format!("{:>10}", n)).concat());
+        let mut counts = Vec::new();
+        for sequence in all_sequences() {
+            let row: Vec<usize> = sizes
+                .iter()
+                .map(|&n| count_comparisons(&mut random_vec(n), &sequence))
+                .collect();
+            let name = format!("{:?}", sequence);
+            println!("{:<12}{}", name, row.iter().map(|c| format!("{:>10}", c)).collect::<String>());
+            counts.push((sequence, row));
+        }
+
+        let largest = |sequence: &GapSequence| counts.iter().find(|(s, _)| s == sequence).unwrap().1[3];
+        // 经验上更好的序列在 4096 个随机元素上比 Shell 原始序列比较次数更少
+        for better in [GapSequence::Knuth, GapSequence::Sedgewick, GapSequence::Ciura, GapSequence::Tokuda] {
+            assert!(largest(&better) < largest(&GapSequence::Shell), "{:?}", better);
+        }
+    }
+}
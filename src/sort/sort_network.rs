@@ -0,0 +1,322 @@
+// 测试特性：排序网络、常量泛型[T; N]、编译期生成比较器表(const fn)、0-1原理验证
+// 语法要点：const N: usize、const { assert!(..) } 编译期检查、static 表由 const fn 构造
+// 功能：对长度不超过 32 的数组按固定的比较-交换序列排序，比较顺序与数据无关，
+//       长度 2..=16 使用已知最优(比较器最少)的网络，17..=32 使用 Batcher 奇偶归并网络；
+//       交换由比较结果算出的下标完成而不是条件分支，除比较函数自身外没有依赖数据的分支，
+//       适合作为快速排序、归并排序递归到底时的基础情形
+
+use std::cmp::Ordering;
+
+use super::instrument::{NoopObserver, SortObserver};
+
+// 支持的最大长度
+pub const MAX_NETWORK_SIZE: usize = 32;
+
+// Batcher 网络在 n = 32 时有 191 个比较器，是表中最多的
+const MAX_COMPARATORS: usize = 191;
+
+// 长度 2..=8 的最优网络（比较器数分别为 1, 3, 5, 9, 12, 16, 19）
+const OPTIMAL_2: [(u8, u8); 1] = [(0, 1)];
+const OPTIMAL_3: [(u8, u8); 3] = [(0, 2), (0, 1), (1, 2)];
+const OPTIMAL_4: [(u8, u8); 5] = [(0, 1), (2, 3), (0, 2), (1, 3), (1, 2)];
+const OPTIMAL_5: [(u8, u8); 9] = [(0, 1), (3, 4), (2, 4), (2, 3), (0, 3), (0, 2), (1, 4), (1, 3), (1, 2)];
+const OPTIMAL_6: [(u8, u8); 12] = [
+    (1, 2), (4, 5), (0, 2), (3, 5), (0, 1), (3, 4),
+    (2, 5), (0, 3), (1, 4), (2, 4), (1, 3), (2, 3),
+];
+const OPTIMAL_7: [(u8, u8); 16] = [
+    (1, 2), (3, 4), (5, 6), (0, 2), (3, 5), (4, 6), (0, 1), (4, 5),
+    (2, 6), (0, 4), (1, 5), (0, 3), (2, 5), (1, 3), (2, 4), (2, 3),
+];
+const OPTIMAL_8: [(u8, u8); 19] = [
+    (0, 2), (1, 3), (4, 6), (5, 7), (0, 4), (1, 5), (2, 6), (3, 7), (0, 1), (2, 3),
+    (4, 5), (6, 7), (2, 4), (3, 5), (1, 4), (3, 6), (1, 2), (3, 4), (5, 6),
+];
+
+// 长度 9..=16 目前已知比较器最少的网络（25, 29, 35, 39, 45, 51, 56, 60），
+// 16 为 Green 网络，15 由它去掉最高一条线得到。9、10 已证明最优，其余为已知最好结果
+const BEST_9: [(u8, u8); 25] = [
+    (0, 3), (1, 7), (2, 5), (4, 8), (0, 7), (2, 4), (3, 8), (5, 6), (0, 2), (1, 3),
+    (4, 5), (7, 8), (1, 4), (3, 6), (5, 7), (0, 1), (2, 4), (3, 5), (6, 8), (2, 3),
+    (4, 5), (6, 7), (1, 2), (3, 4), (5, 6),
+];
+const BEST_10: [(u8, u8); 29] = [
+    (0, 8), (1, 9), (2, 7), (3, 5), (4, 6), (0, 2), (1, 4), (5, 8), (7, 9), (0, 3),
+    (2, 4), (5, 7), (6, 9), (0, 1), (3, 6), (8, 9), (1, 5), (2, 3), (4, 8), (6, 7),
+    (1, 2), (3, 5), (4, 6), (7, 8), (2, 3), (4, 5), (6, 7), (3, 4), (5, 6),
+];
+const BEST_11: [(u8, u8); 35] = [
+    (0, 9), (1, 6), (2, 4), (3, 7), (5, 8), (0, 1), (3, 5), (4, 10), (6, 9), (7, 8),
+    (1, 3), (2, 5), (4, 7), (8, 10), (0, 4), (1, 2), (3, 7), (5, 9), (6, 8), (0, 1),
+    (2, 6), (4, 5), (7, 8), (9, 10), (2, 4), (3, 6), (5, 7), (8, 9), (1, 2), (3, 4),
+    (5, 6), (7, 8), (2, 3), (4, 5), (6, 7),
+];
+const BEST_12: [(u8, u8); 39] = [
+    (0, 8), (1, 7), (2, 6), (3, 11), (4, 10), (5, 9), (0, 1), (2, 5), (3, 4), (6, 9),
+    (7, 8), (10, 11), (0, 2), (1, 6), (5, 10), (9, 11), (0, 3), (1, 2), (4, 6), (5, 7),
+    (8, 11), (9, 10), (1, 4), (3, 5), (6, 8), (7, 10), (1, 3), (2, 5), (6, 9), (8, 10),
+    (2, 3), (4, 5), (6, 7), (8, 9), (4, 6), (5, 7), (3, 4), (5, 6), (7, 8),
+];
+const BEST_13: [(u8, u8); 45] = [
+    (0, 12), (1, 10), (2, 9), (3, 7), (5, 11), (6, 8), (1, 6), (2, 3), (4, 11), (7, 9),
+    (8, 10), (0, 4), (1, 2), (3, 6), (7, 8), (9, 10), (11, 12), (4, 6), (5, 9), (8, 11),
+    (10, 12), (0, 5), (3, 8), (4, 7), (6, 11), (9, 10), (0, 1), (2, 5), (6, 9), (7, 8),
+    (10, 11), (1, 3), (2, 4), (5, 6), (9, 10), (1, 2), (3, 4), (5, 7), (6, 8), (2, 3),
+    (4, 5), (6, 7), (8, 9), (3, 4), (5, 6),
+];
+const BEST_14: [(u8, u8); 51] = [
+    (0, 1), (2, 3), (4, 5), (6, 7), (8, 9), (10, 11), (12, 13), (0, 2), (1, 3), (4, 8),
+    (5, 9), (10, 12), (11, 13), (0, 4), (1, 2), (3, 7), (5, 8), (6, 10), (9, 13), (11, 12),
+    (0, 6), (1, 5), (3, 9), (4, 10), (7, 13), (8, 12), (2, 10), (3, 11), (4, 6), (7, 9),
+    (1, 3), (2, 8), (5, 11), (6, 7), (10, 12), (1, 4), (2, 6), (3, 5), (7, 11), (8, 10),
+    (9, 12), (2, 4), (3, 6), (5, 8), (7, 10), (9, 11), (3, 4), (5, 6), (7, 8), (9, 10),
+    (6, 7),
+];
+const BEST_15: [(u8, u8); 56] = [
+    (0, 13), (1, 12), (3, 14), (4, 8), (5, 6), (7, 11), (9, 10), (0, 5), (1, 7), (2, 9),
+    (3, 4), (6, 13), (8, 14), (11, 12), (0, 1), (2, 3), (4, 5), (6, 8), (7, 9), (10, 11),
+    (12, 13), (0, 2), (1, 3), (4, 10), (5, 11), (6, 7), (8, 9), (12, 14), (1, 2), (3, 12),
+    (4, 6), (5, 7), (8, 10), (9, 11), (13, 14), (1, 4), (2, 6), (5, 8), (7, 10), (9, 13),
+    (11, 14), (2, 4), (3, 6), (9, 12), (11, 13), (3, 5), (6, 8), (7, 9), (10, 12), (3, 4),
+    (5, 6), (7, 8), (9, 10), (11, 12), (6, 7), (8, 9),
+];
+const BEST_16: [(u8, u8); 60] = [
+    (0, 13), (1, 12), (2, 15), (3, 14), (4, 8), (5, 6), (7, 11), (9, 10), (0, 5), (1, 7),
+    (2, 9), (3, 4), (6, 13), (8, 14), (10, 15), (11, 12), (0, 1), (2, 3), (4, 5), (6, 8),
+    (7, 9), (10, 11), (12, 13), (14, 15), (0, 2), (1, 3), (4, 10), (5, 11), (6, 7), (8, 9),
+    (12, 14), (13, 15), (1, 2), (3, 12), (4, 6), (5, 7), (8, 10), (9, 11), (13, 14), (1, 4),
+    (2, 6), (5, 8), (7, 10), (9, 13), (11, 14), (2, 4), (3, 6), (9, 12), (11, 13), (3, 5),
+    (6, 8), (7, 9), (10, 12), (3, 4), (5, 6), (7, 8), (9, 10), (11, 12), (6, 7), (8, 9),
+];
+
+#[derive(Clone, Copy)]
+struct Network {
+    pairs: [(u8, u8); MAX_COMPARATORS],
+    len: usize,
+}
+
+impl Network {
+    const EMPTY: Network = Network {
+        pairs: [(0, 0); MAX_COMPARATORS],
+        len: 0,
+    };
+
+    const fn from_pairs(pairs: &[(u8, u8)]) -> Network {
+        let mut network = Network::EMPTY;
+        while network.len < pairs.len() {
+            network.pairs[network.len] = pairs[network.len];
+            network.len += 1;
+        }
+        network
+    }
+
+    // Batcher 奇偶归并网络，对任意 n 有效：比较器数为 O(n log² n)，
+    // n = 16 时 63 个，多于上面的 60 个，因此只用于 17..=32 这些没有内置表的长度
+    const fn batcher(n: usize) -> Network {
+        let mut network = Network::EMPTY;
+        let mut p = 1;
+        while p < n {
+            let mut k = p;
+            while k >= 1 {
+                let mut j = k % p;
+                while j + k < n {
+                    let mut i = 0;
+                    while i < k && i + j + k < n {
+                        if (i + j) / (2 * p) == (i + j + k) / (2 * p) {
+                            network.pairs[network.len] = ((i + j) as u8, (i + j + k) as u8);
+                            network.len += 1;
+                        }
+                        i += 1;
+                    }
+                    j += 2 * k;
+                }
+                k /= 2;
+            }
+            p *= 2;
+        }
+        network
+    }
+
+    const fn for_size(n: usize) -> Network {
+        match n {
+            2 => Network::from_pairs(&OPTIMAL_2),
+            3 => Network::from_pairs(&OPTIMAL_3),
+            4 => Network::from_pairs(&OPTIMAL_4),
+            5 => Network::from_pairs(&OPTIMAL_5),
+            6 => Network::from_pairs(&OPTIMAL_6),
+            7 => Network::from_pairs(&OPTIMAL_7),
+            8 => Network::from_pairs(&OPTIMAL_8),
+            9 => Network::from_pairs(&BEST_9),
+            10 => Network::from_pairs(&BEST_10),
+            11 => Network::from_pairs(&BEST_11),
+            12 => Network::from_pairs(&BEST_12),
+            13 => Network::from_pairs(&BEST_13),
+            14 => Network::from_pairs(&BEST_14),
+            15 => Network::from_pairs(&BEST_15),
+            16 => Network::from_pairs(&BEST_16),
+            _ => Network::batcher(n),
+        }
+    }
+}
+
+const fn build_networks() -> [Network; MAX_NETWORK_SIZE + 1] {
+    let mut networks = [Network::EMPTY; MAX_NETWORK_SIZE + 1];
+    let mut n = 2;
+    while n <= MAX_NETWORK_SIZE {
+        networks[n] = Network::for_size(n);
+        n += 1;
+    }
+    networks
+}
+
+static NETWORKS: [Network; MAX_NETWORK_SIZE + 1] = build_networks();
+
+// 长度为 n 的网络的比较器序列，每个 (i, j) 满足 i < j，执行后 arr[i] <= arr[j]
+pub fn comparators(n: usize) -> &'static [(u8, u8)] {
+    assert!(n <= MAX_NETWORK_SIZE, "排序网络最多支持 {} 个元素", MAX_NETWORK_SIZE);
+    let network = &NETWORKS[n];
+    &network.pairs[..network.len]
+}
+
+pub fn sort_network<T: PartialOrd, const N: usize>(arr: &mut [T; N]) {
+    sort_network_by(arr, |a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+}
+
+// N 超过 MAX_NETWORK_SIZE 时编译失败
+pub fn sort_network_by<T, F, const N: usize>(arr: &mut [T; N], mut compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    const { assert!(N <= MAX_NETWORK_SIZE, "排序网络最多支持 32 个元素") };
+    network_sort_at(arr, &mut compare, &mut NoopObserver, 0);
+}
+
+// 对长度不超过 MAX_NETWORK_SIZE 的切片执行对应的网络。网络不稳定
+pub(crate) fn network_sort_at<T, F, O>(arr: &mut [T], compare: &mut F, observer: &mut O, base: usize)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver + ?Sized,
+{
+    for &(i, j) in comparators(arr.len()) {
+        compare_exchange(arr, i as usize, j as usize, compare, observer, base);
+    }
+}
+
+// 奇偶换位网络：n 轮相邻比较-交换，只交换严格逆序的相邻元素，因此是稳定的。
+// 比较器数为 n(n-1)/2，只适合很短的区间，供需要稳定性的归并排序使用
+pub(crate) fn transposition_sort_at<T, F, O>(arr: &mut [T], compare: &mut F, observer: &mut O, base: usize)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver + ?Sized,
+{
+    let n = arr.len();
+    for round in 0..n {
+        let mut i = round % 2;
+        while i + 1 < n {
+            compare_exchange(arr, i, i + 1, compare, observer, base);
+            i += 2;
+        }
+    }
+}
+
+// 比较器 (i, j)：arr[j] 严格小于 arr[i] 时交换。
+// 交换不走条件分支：比较结果换算成下标 k (需要交换时为 j，否则为 i)，
+// 然后无条件执行 swap(i, k)，k == i 时这次交换不改变任何元素。
+// 观察者只在真正交换时收到通知，NoopObserver 的空实现内联后这个判断也会消失
+#[inline(always)]
+fn compare_exchange<T, F, O>(arr: &mut [T], i: usize, j: usize, compare: &mut F, observer: &mut O, base: usize)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver + ?Sized,
+{
+    observer.on_compare(base + i, base + j);
+    let less = compare(&arr[j], &arr[i]) == Ordering::Less;
+    let k = i + usize::from(less) * (j - i);
+    arr.swap(i, k);
+    if less {
+        observer.on_swap(base + i, base + j);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sort::rng::Rng;
+
+    // 0-1 原理：网络能排好所有 0/1 序列，就能排好任意序列
+    fn sorts_all_binary_inputs(n: usize) -> bool {
+        (0u64..1 << n).all(|bits| {
+            let mut arr: Vec<u8> = (0..n).map(|i| (bits >> i & 1) as u8).collect();
+            network_sort_at(&mut arr, &mut u8::cmp, &mut NoopObserver, 0);
+            arr.windows(2).all(|w| w[0] <= w[1])
+        })
+    }
+
+    #[test]
+    fn test_zero_one_principle_up_to_16() {
+        for n in 0..=16 {
+            assert!(sorts_all_binary_inputs(n), "n = {}", n);
+        }
+    }
+
+    #[test]
+    fn test_comparator_counts() {
+        let counts: Vec<usize> = (2..=16).map(|n| comparators(n).len()).collect();
+        assert_eq!(counts, vec![1, 3, 5, 9, 12, 16, 19, 25, 29, 35, 39, 45, 51, 56, 60]);
+        // 17 以上为 Batcher 网络
+        assert_eq!(comparators(17).len(), Network::batcher(17).len);
+        assert_eq!(comparators(32).len(), MAX_COMPARATORS);
+        for n in 0..=MAX_NETWORK_SIZE {
+            assert!(comparators(n).iter().all(|&(i, j)| i < j && (j as usize) < n));
+        }
+    }
+
+    #[test]
+    fn test_random_inputs_up_to_32() {
+        let mut rng = Rng::new(8);
+        for n in 0..=MAX_NETWORK_SIZE {
+            for _ in 0..500 {
+                let mut arr: Vec<u32> = (0..n).map(|_| rng.below(8) as u32).collect();
+                let mut expected = arr.clone();
+                expected.sort();
+                network_sort_at(&mut arr, &mut u32::cmp, &mut NoopObserver, 0);
+                assert_eq!(arr, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_sort_network_arrays() {
+        let mut arr = [64, 34, 25, 12, 22, 11, 90];
+        sort_network(&mut arr);
+        assert_eq!(arr, [11, 12, 22, 25, 34, 64, 90]);
+
+        let mut words = [
+            "pear", "fig", "banana", "kiwi", "apple", "plum", "date", "lime", "grape", "melon", "cherry",
+            "lemon", "mango", "olive", "peach", "quince", "papaya", "guava", "lychee", "orange",
+        ];
+        let mut expected = words;
+        expected.sort();
+        sort_network(&mut words);
+        assert_eq!(words, expected);
+
+        let mut desc = [1, 5, 2, 4, 3];
+        sort_network_by(&mut desc, |a, b| b.cmp(a));
+        assert_eq!(desc, [5, 4, 3, 2, 1]);
+
+        let mut empty: [i32; 0] = [];
+        sort_network(&mut empty);
+    }
+
+    #[test]
+    fn test_transposition_is_stable() {
+        let mut rng = Rng::new(4);
+        for n in 0..=12 {
+            let mut arr: Vec<(u64, usize)> = (0..n).map(|i| (rng.below(3), i)).collect();
+            let mut expected = arr.clone();
+            expected.sort_by_key(|&(k, _)| k);
+            transposition_sort_at(&mut arr, &mut |a: &(u64, usize), b: &(u64, usize)| a.0.cmp(&b.0), &mut NoopObserver, 0);
+            assert_eq!(arr, expected);
+        }
+    }
+}
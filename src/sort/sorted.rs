@@ -0,0 +1,448 @@
+// 测试特性：有序序列上的操作、二分查找边界、迭代器适配器、多路归并
+// 语法要点：impl Iterator for 自定义结构体、Peekable、Range<usize>、fn 指针作为默认比较函数
+// 功能：lower_bound/upper_bound/equal_range 二分查找，合并两个有序切片，
+//       有序序列的去重、并集、交集、差集迭代器，以及任意多个有序迭代器的 k 路归并
+
+use std::cmp::Ordering;
+use std::iter::Peekable;
+use std::mem;
+use std::ops::Range;
+
+use super::heap_sort::heapify;
+use super::instrument::NoopObserver;
+
+// 第一个不小于 value 的位置，全部小于 value 时返回 arr.len()
+pub fn lower_bound<T: Ord>(arr: &[T], value: &T) -> usize {
+    lower_bound_by(arr, value, T::cmp)
+}
+
+pub fn lower_bound_by<T, F>(arr: &[T], value: &T, mut compare: F) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    arr.partition_point(|x| compare(x, value) == Ordering::Less)
+}
+
+pub fn lower_bound_by_key<T, K, F>(arr: &[T], key: &K, mut f: F) -> usize
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    arr.partition_point(|x| f(x) < *key)
+}
+
+// 第一个大于 value 的位置
+pub fn upper_bound<T: Ord>(arr: &[T], value: &T) -> usize {
+    upper_bound_by(arr, value, T::cmp)
+}
+
+pub fn upper_bound_by<T, F>(arr: &[T], value: &T, mut compare: F) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    arr.partition_point(|x| compare(x, value) != Ordering::Greater)
+}
+
+pub fn upper_bound_by_key<T, K, F>(arr: &[T], key: &K, mut f: F) -> usize
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    arr.partition_point(|x| f(x) <= *key)
+}
+
+// 与 value 相等的元素所在的区间 [lower_bound, upper_bound)
+pub fn equal_range<T: Ord>(arr: &[T], value: &T) -> Range<usize> {
+    equal_range_by(arr, value, T::cmp)
+}
+
+pub fn equal_range_by<T, F>(arr: &[T], value: &T, mut compare: F) -> Range<usize>
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let start = lower_bound_by(arr, value, &mut compare);
+    // 上界只可能在 start 之后
+    let end = start + upper_bound_by(&arr[start..], value, &mut compare);
+    start..end
+}
+
+pub fn equal_range_by_key<T, K, F>(arr: &[T], key: &K, mut f: F) -> Range<usize>
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    let start = lower_bound_by_key(arr, key, &mut f);
+    let end = start + upper_bound_by_key(&arr[start..], key, &mut f);
+    start..end
+}
+
+// 合并两个有序切片，相等时 a 中的元素在前（稳定）
+pub fn merge_sorted<T: Ord + Clone>(a: &[T], b: &[T]) -> Vec<T> {
+    merge_sorted_by(a, b, T::cmp)
+}
+
+pub fn merge_sorted_by<T, F>(a: &[T], b: &[T], mut compare: F) -> Vec<T>
+where
+    T: Clone,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let mut merged = Vec::with_capacity(a.len() + b.len());
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        if compare(&b[j], &a[i]) == Ordering::Less {
+            merged.push(b[j].clone());
+            j += 1;
+        } else {
+            merged.push(a[i].clone());
+            i += 1;
+        }
+    }
+    merged.extend_from_slice(&a[i..]);
+    merged.extend_from_slice(&b[j..]);
+    merged
+}
+
+// 有序迭代器去重：相邻的相等元素只保留第一个
+pub struct Dedup<I: Iterator, F> {
+    iter: Peekable<I>,
+    compare: F,
+}
+
+// dedup_sorted 返回的迭代器：按 Ord 比较相邻元素
+pub type DefaultDedup<I> = Dedup<I, fn(&<I as Iterator>::Item, &<I as Iterator>::Item) -> Ordering>;
+
+pub fn dedup_sorted<I>(iter: I) -> DefaultDedup<I::IntoIter>
+where
+    I: IntoIterator,
+    I::Item: Ord,
+{
+    dedup_sorted_by(iter, Ord::cmp)
+}
+
+pub fn dedup_sorted_by<I, F>(iter: I, compare: F) -> Dedup<I::IntoIter, F>
+where
+    I: IntoIterator,
+    F: FnMut(&I::Item, &I::Item) -> Ordering,
+{
+    Dedup {
+        iter: iter.into_iter().peekable(),
+        compare,
+    }
+}
+
+impl<I, F> Iterator for Dedup<I, F>
+where
+    I: Iterator,
+    F: FnMut(&I::Item, &I::Item) -> Ordering,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        let item = self.iter.next()?;
+        while let Some(next) = self.iter.peek() {
+            if (self.compare)(&item, next) != Ordering::Equal {
+                break;
+            }
+            self.iter.next();
+        }
+        Some(item)
+    }
+}
+
+// 集合运算的种类。输入中有重复元素时按多重集处理：
+// 并集取两侧出现次数的较大值，交集取较小值，差集取 a 的次数减去 b 的次数
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SetOp {
+    Union,
+    Intersection,
+    Difference,
+}
+
+// 两个有序迭代器的集合运算，输出仍然有序；元素相等时输出 a 中的那一个
+pub struct SetIter<I: Iterator, J: Iterator<Item = I::Item>, F> {
+    a: Peekable<I>,
+    b: Peekable<J>,
+    compare: F,
+    op: SetOp,
+}
+
+pub type DefaultCompare<T> = fn(&T, &T) -> Ordering;
+
+pub fn union<I, J>(a: I, b: J) -> SetIter<I::IntoIter, J::IntoIter, DefaultCompare<I::Item>>
+where
+    I: IntoIterator,
+    J: IntoIterator<Item = I::Item>,
+    I::Item: Ord,
+{
+    union_by(a, b, Ord::cmp)
+}
+
+pub fn union_by<I, J, F>(a: I, b: J, compare: F) -> SetIter<I::IntoIter, J::IntoIter, F>
+where
+    I: IntoIterator,
+    J: IntoIterator<Item = I::Item>,
+    F: FnMut(&I::Item, &I::Item) -> Ordering,
+{
+    SetIter::new(a, b, compare, SetOp::Union)
+}
+
+pub fn intersection<I, J>(a: I, b: J) -> SetIter<I::IntoIter, J::IntoIter, DefaultCompare<I::Item>>
+where
+    I: IntoIterator,
+    J: IntoIterator<Item = I::Item>,
+    I::Item: Ord,
+{
+    intersection_by(a, b, Ord::cmp)
+}
+
+pub fn intersection_by<I, J, F>(a: I, b: J, compare: F) -> SetIter<I::IntoIter, J::IntoIter, F>
+where
+    I: IntoIterator,
+    J: IntoIterator<Item = I::Item>,
+    F: FnMut(&I::Item, &I::Item) -> Ordering,
+{
+    SetIter::new(a, b, compare, SetOp::Intersection)
+}
+
+// a 中有而 b 中没有的元素
+pub fn difference<I, J>(a: I, b: J) -> SetIter<I::IntoIter, J::IntoIter, DefaultCompare<I::Item>>
+where
+    I: IntoIterator,
+    J: IntoIterator<Item = I::Item>,
+    I::Item: Ord,
+{
+    difference_by(a, b, Ord::cmp)
+}
+
+pub fn difference_by<I, J, F>(a: I, b: J, compare: F) -> SetIter<I::IntoIter, J::IntoIter, F>
+where
+    I: IntoIterator,
+    J: IntoIterator<Item = I::Item>,
+    F: FnMut(&I::Item, &I::Item) -> Ordering,
+{
+    SetIter::new(a, b, compare, SetOp::Difference)
+}
+
+impl<I, J, F> SetIter<I, J, F>
+where
+    I: Iterator,
+    J: Iterator<Item = I::Item>,
+    F: FnMut(&I::Item, &I::Item) -> Ordering,
+{
+    fn new<A, B>(a: A, b: B, compare: F, op: SetOp) -> Self
+    where
+        A: IntoIterator<IntoIter = I>,
+        B: IntoIterator<IntoIter = J>,
+    {
+        SetIter {
+            a: a.into_iter().peekable(),
+            b: b.into_iter().peekable(),
+            compare,
+            op,
+        }
+    }
+}
+
+impl<I, J, F> Iterator for SetIter<I, J, F>
+where
+    I: Iterator,
+    J: Iterator<Item = I::Item>,
+    F: FnMut(&I::Item, &I::Item) -> Ordering,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        loop {
+            let order = match (self.a.peek(), self.b.peek()) {
+                (None, None) => return None,
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(x), Some(y)) => (self.compare)(x, y),
+            };
+            match (self.op, order) {
+                (SetOp::Union, Ordering::Less) | (SetOp::Difference, Ordering::Less) => return self.a.next(),
+                (SetOp::Union, Ordering::Greater) => return self.b.next(),
+                (SetOp::Union, Ordering::Equal) | (SetOp::Intersection, Ordering::Equal) => {
+                    self.b.next();
+                    return self.a.next();
+                }
+                // 任一侧耗尽后交集即结束
+                (SetOp::Intersection, Ordering::Less) => {
+                    self.b.peek()?;
+                    self.a.next();
+                }
+                (SetOp::Intersection, Ordering::Greater) => {
+                    self.a.peek()?;
+                    self.b.next();
+                }
+                (SetOp::Difference, Ordering::Greater) => {
+                    self.a.peek()?;
+                    self.b.next();
+                }
+                (SetOp::Difference, Ordering::Equal) => {
+                    self.a.next();
+                    self.b.next();
+                }
+            }
+        }
+    }
+}
+
+// k 路归并：堆中保存每个输入当前的首元素及其来源下标。
+// 与外部排序相同，heapify 维护大顶堆，比较取反后堆顶为最小元素；
+// 相等时来源下标小的优先，因此归并是稳定的
+pub struct KMerge<I: Iterator, F> {
+    sources: Vec<I>,
+    heap: Vec<(I::Item, usize)>,
+    compare: F,
+}
+
+pub fn kmerge<I>(iters: I) -> KMerge<<I::Item as IntoIterator>::IntoIter, DefaultCompare<<I::Item as IntoIterator>::Item>>
+where
+    I: IntoIterator,
+    I::Item: IntoIterator,
+    <I::Item as IntoIterator>::Item: Ord,
+{
+    kmerge_by(iters, Ord::cmp)
+}
+
+pub fn kmerge_by<I, F>(iters: I, compare: F) -> KMerge<<I::Item as IntoIterator>::IntoIter, F>
+where
+    I: IntoIterator,
+    I::Item: IntoIterator,
+    F: FnMut(&<I::Item as IntoIterator>::Item, &<I::Item as IntoIterator>::Item) -> Ordering,
+{
+    let mut sources: Vec<_> = iters.into_iter().map(IntoIterator::into_iter).collect();
+    let heap = sources
+        .iter_mut()
+        .enumerate()
+        .filter_map(|(source, iter)| iter.next().map(|item| (item, source)))
+        .collect();
+    let mut merge = KMerge { sources, heap, compare };
+    let n = merge.heap.len();
+    for i in (0..n / 2).rev() {
+        merge.sift_down(i);
+    }
+    merge
+}
+
+impl<I, F> KMerge<I, F>
+where
+    I: Iterator,
+    F: FnMut(&I::Item, &I::Item) -> Ordering,
+{
+    fn sift_down(&mut self, i: usize) {
+        let compare = &mut self.compare;
+        let mut min_first = |a: &(I::Item, usize), b: &(I::Item, usize)| compare(&b.0, &a.0).then_with(|| b.1.cmp(&a.1));
+        let n = self.heap.len();
+        heapify(&mut self.heap, n, i, &mut min_first, &mut NoopObserver, 0);
+    }
+}
+
+impl<I, F> Iterator for KMerge<I, F>
+where
+    I: Iterator,
+    F: FnMut(&I::Item, &I::Item) -> Ordering,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        let source = self.heap.first()?.1;
+        let item = match self.sources[source].next() {
+            Some(next) => mem::replace(&mut self.heap[0].0, next),
+            None => self.heap.swap_remove(0).0,
+        };
+        self.sift_down(0);
+        Some(item)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bounds() {
+        let arr = [1, 2, 2, 2, 5, 8];
+        assert_eq!(lower_bound(&arr, &2), 1);
+        assert_eq!(upper_bound(&arr, &2), 4);
+        assert_eq!(equal_range(&arr, &2), 1..4);
+        assert_eq!(equal_range(&arr, &3), 4..4);
+        assert_eq!(lower_bound(&arr, &0), 0);
+        assert_eq!(upper_bound(&arr, &9), 6);
+        assert_eq!(equal_range::<i32>(&[], &1), 0..0);
+
+        // 降序数组配合反向比较函数
+        let desc = [9, 7, 7, 3];
+        assert_eq!(equal_range_by(&desc, &7, |a, b| b.cmp(a)), 1..3);
+
+        let words = ["fig", "kiwi", "pear", "apple", "banana"];
+        assert_eq!(equal_range_by_key(&words, &4, |w| w.len()), 1..3);
+        assert_eq!(lower_bound_by_key(&words, &5, |w| w.len()), 3);
+        assert_eq!(upper_bound_by_key(&words, &5, |w| w.len()), 4);
+    }
+
+    #[test]
+    fn test_bounds_match_linear_scan() {
+        let arr: Vec<u32> = (0..200).map(|x| x / 3).collect();
+        for value in 0..70 {
+            let lower = arr.iter().position(|&x| x >= value).unwrap_or(arr.len());
+            let upper = arr.iter().position(|&x| x > value).unwrap_or(arr.len());
+            assert_eq!(equal_range(&arr, &value), lower..upper);
+        }
+    }
+
+    #[test]
+    fn test_merge_sorted() {
+        assert_eq!(merge_sorted(&[1, 4, 6], &[2, 3, 7, 8]), vec![1, 2, 3, 4, 6, 7, 8]);
+        assert_eq!(merge_sorted(&[], &[1, 2]), vec![1, 2]);
+
+        let a = [(1, 'a'), (2, 'a')];
+        let b = [(1, 'b'), (2, 'b')];
+        let merged = merge_sorted_by(&a, &b, |x, y| x.0.cmp(&y.0));
+        assert_eq!(merged, vec![(1, 'a'), (1, 'b'), (2, 'a'), (2, 'b')]);
+    }
+
+    #[test]
+    fn test_dedup() {
+        let unique: Vec<_> = dedup_sorted(vec![1, 1, 2, 3, 3, 3, 4]).collect();
+        assert_eq!(unique, vec![1, 2, 3, 4]);
+        let by_len: Vec<_> = dedup_sorted_by(["a", "b", "cc", "dd", "eee"], |x, y| x.len().cmp(&y.len())).collect();
+        assert_eq!(by_len, vec!["a", "cc", "eee"]);
+        assert_eq!(dedup_sorted(Vec::<i32>::new()).count(), 0);
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a = [1, 2, 2, 4, 6, 8];
+        let b = [2, 3, 4, 4, 8, 9];
+        assert_eq!(union(a, b).collect::<Vec<_>>(), vec![1, 2, 2, 3, 4, 4, 6, 8, 9]);
+        assert_eq!(intersection(a, b).collect::<Vec<_>>(), vec![2, 4, 8]);
+        assert_eq!(difference(a, b).collect::<Vec<_>>(), vec![1, 2, 6]);
+        assert_eq!(difference(b, a).collect::<Vec<_>>(), vec![3, 4, 9]);
+        assert_eq!(intersection([1, 2], Vec::<i32>::new()).count(), 0);
+        assert_eq!(union(Vec::<i32>::new(), [5]).collect::<Vec<_>>(), vec![5]);
+
+        // 自定义比较：忽略大小写
+        let lower = |x: &&str, y: &&str| x.to_lowercase().cmp(&y.to_lowercase());
+        let common: Vec<_> = intersection_by(["Apple", "fig", "Pear"], ["apple", "Pear"], lower).collect();
+        assert_eq!(common, vec!["Apple", "Pear"]);
+    }
+
+    #[test]
+    fn test_kmerge() {
+        let merged: Vec<_> = kmerge(vec![vec![1, 4, 7], vec![2, 5, 8], vec![], vec![0, 3, 6, 9]]).collect();
+        assert_eq!(merged, (0..10).collect::<Vec<_>>());
+        assert_eq!(kmerge(Vec::<Vec<i32>>::new()).count(), 0);
+
+        // 相等时按来源顺序输出
+        let sources = vec![vec![(1, 'a'), (2, 'a')], vec![(1, 'b')], vec![(1, 'c'), (2, 'c')]];
+        let merged: Vec<_> = kmerge_by(sources, |x, y| x.0.cmp(&y.0)).collect();
+        assert_eq!(merged, vec![(1, 'a'), (1, 'b'), (1, 'c'), (2, 'a'), (2, 'c')]);
+
+        // 惰性地归并无限迭代器
+        let multiples = (1..4u64).map(|k| (1..).map(move |i| i * k));
+        let first: Vec<_> = kmerge(multiples).take(8).collect();
+        assert_eq!(first, vec![1, 2, 2, 3, 3, 4, 4, 5]);
+    }
+}
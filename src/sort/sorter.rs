@@ -0,0 +1,557 @@
+// 测试特性：Trait对象、泛型Trait、关联常量、运行时多态分发
+// 语法要点：trait Sorter<T>、Box<dyn Trait>、impl块按具体类型特化、&'static引用
+// 功能：为所有排序算法提供统一接口和元数据，并通过注册表按名称在运行时选择算法
+
+use std::cmp::Ordering;
+
+use super::bubble_sort::bubble_sort_observed;
+use super::bucket_sort::bucket_sort_observed;
+use super::counting_sort::counting_sort_observed;
+use super::heap_sort::heap_sort_observed;
+use super::insertion_sort::insertion_sort_observed;
+use super::instrument::SortObserver;
+use super::merge_sort::merge_sort_observed;
+use super::quick_sort::quick_sort_observed;
+use super::radix_sort::{radix_sort_observed, RadixKey};
+use super::selection_sort::selection_sort_observed;
+use super::shell_sort::{shell_sort_observed, GapSequence};
+use super::tim_sort::tim_sort_observed;
+use super::{
+    bubble_sort, bucket_sort, counting_sort_auto, heap_sort, insertion_sort, merge_sort, quick_sort,
+    radix_sort, selection_sort, shell_sort, tim_sort,
+};
+
+// 时间/空间复杂度描述
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Complexity {
+    pub best: &'static str,
+    pub average: &'static str,
+    pub worst: &'static str,
+    pub space: &'static str,
+}
+
+// 排序算法的静态元数据
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SortInfo {
+    pub name: &'static str,
+    pub stable: bool,
+    // 平均复杂度为 O(n²)，基准测试只在小规模输入上运行这类算法
+    pub quadratic: bool,
+    pub complexity: Complexity,
+}
+
+// 统一的排序接口，T 为可排序的元素类型
+pub trait Sorter<T> {
+    fn info(&self) -> &'static SortInfo;
+
+    fn name(&self) -> &'static str {
+        self.info().name
+    }
+
+    fn is_stable(&self) -> bool {
+        self.info().stable
+    }
+
+    fn is_quadratic(&self) -> bool {
+        self.info().quadratic
+    }
+
+    fn complexity(&self) -> Complexity {
+        self.info().complexity
+    }
+
+    fn sort(&self, arr: &mut [T]);
+
+    // 排序的同时把比较、交换、写入和分配通知给观察者
+    fn sort_observed(&self, arr: &mut [T], observer: &mut dyn SortObserver);
+}
+
+fn ascending<T: PartialOrd>(a: &T, b: &T) -> Ordering {
+    a.partial_cmp(b).unwrap_or(Ordering::Equal)
+}
+
+pub struct BubbleSort;
+pub struct SelectionSort;
+pub struct InsertionSort;
+pub struct MergeSort;
+pub struct QuickSort;
+pub struct HeapSort;
+pub struct ShellSort;
+pub struct RadixSort;
+pub struct CountingSort;
+pub struct BucketSort;
+pub struct TimSort;
+
+impl BubbleSort {
+    pub const INFO: SortInfo = SortInfo {
+        name: "bubble",
+        stable: true,
+        quadratic: true,
+        // bubble_sort 没有"本轮无交换则提前结束"的判断，已有序的输入也要比较约 n²/2 次
+        complexity: Complexity { best: "O(n²)", average: "O(n²)", worst: "O(n²)", space: "O(1)" },
+    };
+}
+
+impl SelectionSort {
+    pub const INFO: SortInfo = SortInfo {
+        name: "selection",
+        stable: false,
+        quadratic: true,
+        complexity: Complexity { best: "O(n²)", average: "O(n²)", worst: "O(n²)", space: "O(1)" },
+    };
+}
+
+impl InsertionSort {
+    pub const INFO: SortInfo = SortInfo {
+        name: "insertion",
+        stable: true,
+        quadratic: true,
+        complexity: Complexity { best: "O(n)", average: "O(n²)", worst: "O(n²)", space: "O(1)" },
+    };
+}
+
+impl MergeSort {
+    pub const INFO: SortInfo = SortInfo {
+        name: "merge",
+        stable: true,
+        quadratic: false,
+        complexity: Complexity {
+            best: "O(n log n)",
+            average: "O(n log n)",
+            worst: "O(n log n)",
+            space: "O(n)",
+        },
+    };
+}
+
+impl TimSort {
+    pub const INFO: SortInfo = SortInfo {
+        name: "tim",
+        stable: true,
+        quadratic: false,
+        complexity: Complexity {
+            best: "O(n)",
+            average: "O(n log n)",
+            worst: "O(n log n)",
+            space: "O(n)",
+        },
+    };
+}
+
+impl QuickSort {
+    pub const INFO: SortInfo = SortInfo {
+        name: "quick",
+        stable: false,
+        quadratic: false,
+        complexity: Complexity {
+            best: "O(n log n)",
+            average: "O(n log n)",
+            worst: "O(n log n)",
+            space: "O(log n)",
+        },
+    };
+}
+
+impl HeapSort {
+    pub const INFO: SortInfo = SortInfo {
+        name: "heap",
+        stable: false,
+        quadratic: false,
+        complexity: Complexity {
+            best: "O(n log n)",
+            average: "O(n log n)",
+            worst: "O(n log n)",
+            space: "O(1)",
+        },
+    };
+}
+
+impl ShellSort {
+    pub const INFO: SortInfo = SortInfo {
+        name: "shell",
+        stable: false,
+        quadratic: false,
+        complexity: Complexity {
+            best: "O(n log n)",
+            average: "O(n^1.5)",
+            worst: "O(n²)",
+            space: "O(1)",
+        },
+    };
+}
+
+impl RadixSort {
+    pub const INFO: SortInfo = SortInfo {
+        name: "radix",
+        stable: true,
+        quadratic: false,
+        complexity: Complexity { best: "O(d·n)", average: "O(d·n)", worst: "O(d·n)", space: "O(n)" },
+    };
+}
+
+impl CountingSort {
+    pub const INFO: SortInfo = SortInfo {
+        name: "counting",
+        stable: true,
+        quadratic: false,
+        complexity: Complexity { best: "O(n+k)", average: "O(n+k)", worst: "O(n+k)", space: "O(n+k)" },
+    };
+}
+
+impl BucketSort {
+    pub const INFO: SortInfo = SortInfo {
+        name: "bucket",
+        stable: true,
+        quadratic: false,
+        complexity: Complexity { best: "O(n)", average: "O(n)", worst: "O(n²)", space: "O(n)" },
+    };
+}
+
+// 全部十一种算法的元数据，按 sort 模块中的声明顺序排列
+pub static ALGORITHMS: [SortInfo; 11] = [
+    BubbleSort::INFO,
+    SelectionSort::INFO,
+    InsertionSort::INFO,
+    MergeSort::INFO,
+    QuickSort::INFO,
+    HeapSort::INFO,
+    TimSort::INFO,
+    RadixSort::INFO,
+    CountingSort::INFO,
+    ShellSort::INFO,
+    BucketSort::INFO,
+];
+
+// 按名称查找算法元数据
+pub fn find_algorithm(name: &str) -> Option<&'static SortInfo> {
+    ALGORITHMS.iter().find(|info| info.name == name)
+}
+
+impl<T: PartialOrd> Sorter<T> for BubbleSort {
+    fn info(&self) -> &'static SortInfo {
+        &Self::INFO
+    }
+
+    fn sort(&self, arr: &mut [T]) {
+        bubble_sort(arr);
+    }
+
+    fn sort_observed(&self, arr: &mut [T], observer: &mut dyn SortObserver) {
+        bubble_sort_observed(arr, ascending, observer);
+    }
+}
+
+impl<T: PartialOrd> Sorter<T> for SelectionSort {
+    fn info(&self) -> &'static SortInfo {
+        &Self::INFO
+    }
+
+    fn sort(&self, arr: &mut [T]) {
+        selection_sort(arr);
+    }
+
+    fn sort_observed(&self, arr: &mut [T], observer: &mut dyn SortObserver) {
+        selection_sort_observed(arr, ascending, observer);
+    }
+}
+
+impl<T: PartialOrd> Sorter<T> for InsertionSort {
+    fn info(&self) -> &'static SortInfo {
+        &Self::INFO
+    }
+
+    fn sort(&self, arr: &mut [T]) {
+        insertion_sort(arr);
+    }
+
+    fn sort_observed(&self, arr: &mut [T], observer: &mut dyn SortObserver) {
+        insertion_sort_observed(arr, ascending, observer);
+    }
+}
+
+impl<T: PartialOrd> Sorter<T> for MergeSort {
+    fn info(&self) -> &'static SortInfo {
+        &Self::INFO
+    }
+
+    fn sort(&self, arr: &mut [T]) {
+        merge_sort(arr);
+    }
+
+    fn sort_observed(&self, arr: &mut [T], observer: &mut dyn SortObserver) {
+        merge_sort_observed(arr, ascending, observer);
+    }
+}
+
+impl<T: PartialOrd> Sorter<T> for TimSort {
+    fn info(&self) -> &'static SortInfo {
+        &Self::INFO
+    }
+
+    fn sort(&self, arr: &mut [T]) {
+        tim_sort(arr);
+    }
+
+    fn sort_observed(&self, arr: &mut [T], observer: &mut dyn SortObserver) {
+        tim_sort_observed(arr, ascending, observer);
+    }
+}
+
+impl<T: PartialOrd> Sorter<T> for QuickSort {
+    fn info(&self) -> &'static SortInfo {
+        &Self::INFO
+    }
+
+    fn sort(&self, arr: &mut [T]) {
+        quick_sort(arr);
+    }
+
+    fn sort_observed(&self, arr: &mut [T], observer: &mut dyn SortObserver) {
+        quick_sort_observed(arr, ascending, observer);
+    }
+}
+
+impl<T: PartialOrd> Sorter<T> for HeapSort {
+    fn info(&self) -> &'static SortInfo {
+        &Self::INFO
+    }
+
+    fn sort(&self, arr: &mut [T]) {
+        heap_sort(arr);
+    }
+
+    fn sort_observed(&self, arr: &mut [T], observer: &mut dyn SortObserver) {
+        heap_sort_observed(arr, ascending, observer);
+    }
+}
+
+impl<T: PartialOrd> Sorter<T> for ShellSort {
+    fn info(&self) -> &'static SortInfo {
+        &Self::INFO
+    }
+
+    fn sort(&self, arr: &mut [T]) {
+        shell_sort(arr);
+    }
+
+    fn sort_observed(&self, arr: &mut [T], observer: &mut dyn SortObserver) {
+        shell_sort_observed(arr, &GapSequence::Shell, ascending, observer);
+    }
+}
+
+// 非比较排序只支持特定的元素类型
+impl<K: RadixKey> Sorter<K> for RadixSort {
+    fn info(&self) -> &'static SortInfo {
+        &Self::INFO
+    }
+
+    fn sort(&self, arr: &mut [K]) {
+        radix_sort(arr);
+    }
+
+    fn sort_observed(&self, arr: &mut [K], observer: &mut dyn SortObserver) {
+        radix_sort_observed(arr, observer);
+    }
+}
+
+impl<K: RadixKey> Sorter<K> for CountingSort {
+    fn info(&self) -> &'static SortInfo {
+        &Self::INFO
+    }
+
+    // 键的取值范围过大时无法分配计数数组，退化为同样稳定的基数排序
+    fn sort(&self, arr: &mut [K]) {
+        if counting_sort_auto(arr).is_err() {
+            radix_sort(arr);
+        }
+    }
+
+    fn sort_observed(&self, arr: &mut [K], observer: &mut dyn SortObserver) {
+        if counting_sort_observed(arr, observer).is_err() {
+            radix_sort_observed(arr, observer);
+        }
+    }
+}
+
+impl Sorter<f64> for BucketSort {
+    fn info(&self) -> &'static SortInfo {
+        &Self::INFO
+    }
+
+    fn sort(&self, arr: &mut [f64]) {
+        bucket_sort(arr);
+    }
+
+    fn sort_observed(&self, arr: &mut [f64], observer: &mut dyn SortObserver) {
+        bucket_sort_observed(arr, arr.len(), observer);
+    }
+}
+
+// 排序器注册表：保存一组适用于元素类型 T 的排序器，可按名称查找
+pub struct Registry<T> {
+    sorters: Vec<Box<dyn Sorter<T>>>,
+}
+
+impl<T> Registry<T> {
+    pub fn new() -> Self {
+        Registry { sorters: Vec::new() }
+    }
+
+    // 注册排序器，同名的旧排序器会被替换
+    pub fn register<S: Sorter<T> + 'static>(&mut self, sorter: S) -> &mut Self {
+        let name = sorter.name();
+        self.sorters.retain(|s| s.name() != name);
+        self.sorters.push(Box::new(sorter));
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<&dyn Sorter<T>> {
+        self.sorters
+            .iter()
+            .find(|s| s.name() == name)
+            .map(|s| s.as_ref())
+    }
+
+    pub fn names(&self) -> Vec<&'static str> {
+        self.sorters.iter().map(|s| s.name()).collect()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &dyn Sorter<T>> {
+        self.sorters.iter().map(|s| s.as_ref())
+    }
+
+    pub fn len(&self) -> usize {
+        self.sorters.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sorters.is_empty()
+    }
+
+    // 按名称排序，找不到算法时返回错误信息
+    pub fn sort(&self, name: &str, arr: &mut [T]) -> Result<(), String> {
+        match self.get(name) {
+            Some(sorter) => {
+                sorter.sort(arr);
+                Ok(())
+            }
+            None => Err(format!(
+                "未知的排序算法: {} (可选: {})",
+                name,
+                self.names().join(", ")
+            )),
+        }
+    }
+}
+
+impl<T: PartialOrd + 'static> Registry<T> {
+    // 只包含八种比较排序，适用于任意 PartialOrd 类型
+    pub fn comparison() -> Self {
+        let mut registry = Registry::new();
+        registry
+            .register(BubbleSort)
+            .register(SelectionSort)
+            .register(InsertionSort)
+            .register(MergeSort)
+            .register(TimSort)
+            .register(QuickSort)
+            .register(HeapSort)
+            .register(ShellSort);
+        registry
+    }
+}
+
+impl Registry<u32> {
+    pub fn all() -> Self {
+        let mut registry = Registry::comparison();
+        registry.register(RadixSort).register(CountingSort);
+        registry
+    }
+}
+
+impl Registry<usize> {
+    pub fn all() -> Self {
+        let mut registry = Registry::comparison();
+        registry.register(RadixSort).register(CountingSort);
+        registry
+    }
+}
+
+impl Registry<f64> {
+    pub fn all() -> Self {
+        let mut registry = Registry::comparison();
+        registry.register(BucketSort);
+        registry
+    }
+}
+
+impl<T> Default for Registry<T> {
+    fn default() -> Self {
+        Registry::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_algorithms_cover_every_module() {
+        let names: Vec<_> = ALGORITHMS.iter().map(|info| info.name).collect();
+        assert_eq!(
+            names,
+            vec![
+                "bubble", "selection", "insertion", "merge", "quick", "heap", "tim",
+                "radix", "counting", "shell", "bucket"
+            ]
+        );
+        assert_eq!(find_algorithm("merge").map(|info| info.stable), Some(true));
+        let quadratic: Vec<_> = ALGORITHMS.iter().filter(|info| info.quadratic).map(|info| info.name).collect();
+        assert_eq!(quadratic, vec!["bubble", "selection", "insertion"]);
+        assert!(find_algorithm("bogo").is_none());
+    }
+
+    #[test]
+    fn test_registry_sorts_by_name() {
+        let registry = Registry::<u32>::all();
+        for name in registry.names() {
+            let mut arr = vec![64, 34, 25, 12, 22, 11, 90];
+            registry.sort(name, &mut arr).unwrap();
+            assert_eq!(arr, vec![11, 12, 22, 25, 34, 64, 90], "{}", name);
+        }
+        assert!(registry.sort("bogo", &mut [1u32]).is_err());
+    }
+
+    #[test]
+    fn test_typed_registries() {
+        let mut counts = vec![4usize, 2, 2, 8, 3, 3, 1];
+        Registry::<usize>::all().sort("counting", &mut counts).unwrap();
+        assert_eq!(counts, vec![1, 2, 2, 3, 3, 4, 8]);
+
+        let mut floats = vec![0.897, 0.565, 0.656, 0.1234];
+        Registry::<f64>::all().sort("bucket", &mut floats).unwrap();
+        assert_eq!(floats, vec![0.1234, 0.565, 0.656, 0.897]);
+
+        let strings = Registry::<String>::comparison();
+        assert_eq!(strings.len(), 8);
+        assert!(strings.get("radix").is_none());
+
+        // 比较排序只需要 PartialOrd，元素类型不必实现 Clone
+        #[derive(Debug, PartialEq, PartialOrd)]
+        struct Token(u8);
+        let comparison = Registry::<Token>::comparison();
+        for name in comparison.names() {
+            let mut tokens = vec![Token(3), Token(1), Token(2)];
+            comparison.sort(name, &mut tokens).unwrap();
+            assert_eq!(tokens, vec![Token(1), Token(2), Token(3)], "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_register_replaces_same_name() {
+        let mut registry = Registry::<i32>::new();
+        registry.register(QuickSort).register(QuickSort);
+        assert_eq!(registry.names(), vec!["quick"]);
+        assert_eq!(registry.get("quick").unwrap().complexity().worst, "O(n log n)");
+    }
+}
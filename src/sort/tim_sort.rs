@@ -0,0 +1,559 @@
+// 测试特性：自适应归并排序(Timsort)、自然有序段检测、飞奔模式(galloping)
+// 语法要点：带标签的 break 'outer、指数搜索、ptr::copy 处理重叠区间、复用 MergeHole 守卫
+// 功能：扫描输入中已有的升序段(严格降序段原地翻转)，短段用插入排序补足到 minrun，
+//       按 Timsort 的栈不变式合并相邻段；某一侧连续胜出时切换为指数搜索成块搬移，
+//       近乎有序的输入只需 O(n) 次比较
+
+use std::cmp::Ordering;
+use std::mem;
+use std::ptr;
+
+use super::insertion_sort::insertion_sort_at;
+use super::instrument::{NoopObserver, SortObserver};
+use super::merge_sort::MergeHole;
+
+// 短于该长度的数组直接用插入排序
+const MIN_MERGE: usize = 64;
+// 进入飞奔模式所需的初始连胜次数
+const MIN_GALLOP: usize = 7;
+
+pub fn tim_sort<T: PartialOrd>(arr: &mut [T]) {
+    tim_sort_by(arr, |a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+}
+
+pub fn tim_sort_desc<T: PartialOrd>(arr: &mut [T]) {
+    tim_sort_by(arr, |a, b| b.partial_cmp(a).unwrap_or(Ordering::Equal));
+}
+
+pub fn tim_sort_by<T, F>(arr: &mut [T], compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    tim_sort_observed(arr, compare, &mut NoopObserver);
+}
+
+pub fn tim_sort_by_key<T, K, F>(arr: &mut [T], mut key: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    tim_sort_by(arr, |a, b| key(a).cmp(&key(b)));
+}
+
+pub fn tim_sort_observed<T, F, O>(arr: &mut [T], mut compare: F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver + ?Sized,
+{
+    let len = arr.len();
+    if len <= 1 || mem::size_of::<T>() == 0 {
+        return;
+    }
+    if len < MIN_MERGE {
+        count_run(arr, &mut compare, observer, 0);
+        insertion_sort_at(arr, &mut compare, observer, 0);
+        return;
+    }
+
+    let min_run = min_run_length(len);
+    observer.on_alloc(len / 2);
+    let mut buffer: Vec<T> = Vec::with_capacity(len / 2);
+    let mut min_gallop = MIN_GALLOP;
+    let mut runs: Vec<Run> = Vec::new();
+    let mut start = 0;
+    while start < len {
+        let mut run_len = count_run(&mut arr[start..], &mut compare, observer, start);
+        if run_len < min_run {
+            run_len = min_run.min(len - start);
+            insertion_sort_at(&mut arr[start..start + run_len], &mut compare, observer, start);
+        }
+        runs.push(Run { start, len: run_len });
+        start += run_len;
+
+        while let Some(r) = collapse(&runs, len) {
+            let (left, right) = (runs[r], runs[r + 1]);
+            let merged = &mut arr[left.start..right.start + right.len];
+            // SAFETY: 合并时暂存区只保存较短的一段，长度不超过 len / 2
+            unsafe {
+                merge_at(merged, left.len, buffer.as_mut_ptr(), &mut min_gallop, &mut compare, observer, left.start)
+            };
+            runs[r].len += right.len;
+            runs.remove(r + 1);
+        }
+    }
+}
+
+// 栈中一段已经有序的区间 arr[start..start + len]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Run {
+    start: usize,
+    len: usize,
+}
+
+// n 小于 64 时为 n 本身，否则取 n 的最高 6 位，剩余位不全为 0 时再加 1，
+// 使 n / minrun 恰好是或略小于 2 的幂，最后几次合并两侧长度接近
+pub(crate) fn min_run_length(mut n: usize) -> usize {
+    let mut remainder = 0;
+    while n >= MIN_MERGE {
+        remainder |= n & 1;
+        n >>= 1;
+    }
+    n + remainder
+}
+
+// 返回 arr 开头自然有序段的长度；严格降序段会被翻转成升序（严格才能保证稳定）
+fn count_run<T, F, O>(arr: &mut [T], compare: &mut F, observer: &mut O, base: usize) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver + ?Sized,
+{
+    let len = arr.len();
+    if len < 2 {
+        return len;
+    }
+    let mut end = 2;
+    observer.on_compare(base + 1, base);
+    if compare(&arr[1], &arr[0]) == Ordering::Less {
+        while end < len {
+            observer.on_compare(base + end, base + end - 1);
+            if compare(&arr[end], &arr[end - 1]) != Ordering::Less {
+                break;
+            }
+            end += 1;
+        }
+        for i in 0..end / 2 {
+            observer.on_swap(base + i, base + end - 1 - i);
+            arr.swap(i, end - 1 - i);
+        }
+    } else {
+        while end < len {
+            observer.on_compare(base + end, base + end - 1);
+            if compare(&arr[end], &arr[end - 1]) == Ordering::Less {
+                break;
+            }
+            end += 1;
+        }
+    }
+    end
+}
+
+// 需要合并时返回栈中待合并的左侧段下标 r（与 r + 1 合并）。
+// 保持 runs[i] > runs[i+1] + runs[i+2] 且 runs[i] > runs[i+1]，
+// 检查栈顶四段以避免原始 Timsort 不变式的漏洞；最后一段到达末尾时合并全部
+fn collapse(runs: &[Run], len: usize) -> Option<usize> {
+    let n = runs.len();
+    if n >= 2
+        && (runs[n - 1].start + runs[n - 1].len == len
+            || runs[n - 2].len <= runs[n - 1].len
+            || (n >= 3 && runs[n - 3].len <= runs[n - 2].len + runs[n - 1].len)
+            || (n >= 4 && runs[n - 4].len <= runs[n - 3].len + runs[n - 2].len))
+    {
+        if n >= 3 && runs[n - 3].len < runs[n - 1].len {
+            Some(n - 3)
+        } else {
+            Some(n - 2)
+        }
+    } else {
+        None
+    }
+}
+
+// 指数搜索：is_before 在 [0, len) 上为 true 的部分是一段前缀，返回该前缀的长度。
+// from_end 为 true 时从末尾开始探测，答案靠近末尾时只需 O(log k) 次比较
+fn gallop<P: FnMut(usize) -> bool>(len: usize, from_end: bool, mut is_before: P) -> usize {
+    // 答案位于 [lo, hi]，且 is_before(lo - 1) 为 true、is_before(hi) 为 false（越界除外）
+    let (mut lo, mut hi) = (0, len);
+    if from_end {
+        let mut offset = 1;
+        while offset <= len {
+            let i = len - offset;
+            if is_before(i) {
+                lo = i + 1;
+                break;
+            }
+            hi = i;
+            offset *= 2;
+        }
+    } else {
+        let mut i = 0;
+        while i < len {
+            if !is_before(i) {
+                hi = i;
+                break;
+            }
+            lo = i + 1;
+            i = 2 * i + 1;
+        }
+    }
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        if is_before(mid) {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+    lo
+}
+
+// 合并相邻有序段 arr[..mid] 与 arr[mid..]。
+// 先用飞奔搜索去掉已经就位的左侧前缀和右侧后缀，再把较短的一段移入暂存区合并。
+//
+// SAFETY: buf 必须指向至少 min(mid, arr.len() - mid) 个元素的空闲空间，且与 arr 不重叠
+unsafe fn merge_at<T, F, O>(
+    arr: &mut [T],
+    mid: usize,
+    buf: *mut T,
+    min_gallop: &mut usize,
+    compare: &mut F,
+    observer: &mut O,
+    base: usize,
+) where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver + ?Sized,
+{
+    let len = arr.len();
+    let v = arr.as_mut_ptr();
+    // 左段中不大于右段首元素的前缀已经在最终位置
+    let lo = gallop(mid, false, |i| {
+        observer.on_compare(base + mid, base + i);
+        compare(&*v.add(mid), &*v.add(i)) != Ordering::Less
+    });
+    if lo == mid {
+        return;
+    }
+    // 右段中不小于左段末元素的后缀也已经在最终位置
+    let hi = mid
+        + gallop(len - mid, true, |i| {
+            observer.on_compare(base + mid + i, base + mid - 1);
+            compare(&*v.add(mid + i), &*v.add(mid - 1)) == Ordering::Less
+        });
+
+    observer.on_merge(base + lo, base + mid, base + hi);
+    let arr = &mut arr[lo..hi];
+    let (mid, base) = (mid - lo, base + lo);
+    if mid <= arr.len() - mid {
+        merge_lo(arr, mid, buf, min_gallop, compare, observer, base);
+    } else {
+        merge_hi(arr, mid, buf, min_gallop, compare, observer, base);
+    }
+}
+
+// 左段较短：把左段移入暂存区，从前往后写回。
+// 任意时刻 arr[dest..dest + (mid - a)] 都是留给暂存区剩余元素的空洞
+unsafe fn merge_lo<T, F, O>(
+    arr: &mut [T],
+    mid: usize,
+    buf: *mut T,
+    min_gallop: &mut usize,
+    compare: &mut F,
+    observer: &mut O,
+    base: usize,
+) where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver + ?Sized,
+{
+    let len = arr.len();
+    let v = arr.as_mut_ptr();
+    ptr::copy_nonoverlapping(v, buf, mid);
+    let mut hole = MergeHole {
+        start: buf,
+        end: buf.add(mid),
+        dest: v,
+    };
+    // a 为暂存区读取位置，b 为右段读取位置，dest 为写入位置
+    let (mut a, mut b, mut dest) = (0, mid, 0);
+
+    'outer: while a < mid && b < len {
+        // 逐个比较，直到某一侧连续胜出 min_gallop 次
+        let (mut wins_a, mut wins_b) = (0, 0);
+        while a < mid && b < len {
+            observer.on_compare(base + b, base + a);
+            if compare(&*v.add(b), &*buf.add(a)) == Ordering::Less {
+                observer.on_write(base + dest, base + b);
+                ptr::copy_nonoverlapping(v.add(b), v.add(dest), 1);
+                b += 1;
+                wins_b += 1;
+                wins_a = 0;
+            } else {
+                observer.on_write(base + dest, base + a);
+                ptr::copy_nonoverlapping(buf.add(a), v.add(dest), 1);
+                a += 1;
+                wins_a += 1;
+                wins_b = 0;
+                hole.start = buf.add(a);
+            }
+            dest += 1;
+            hole.dest = v.add(dest);
+            if wins_a >= *min_gallop || wins_b >= *min_gallop {
+                break;
+            }
+        }
+
+        // 飞奔模式：用指数搜索找出每一侧可以整块搬移的元素个数
+        while a < mid && b < len {
+            let count_a = gallop(mid - a, false, |i| {
+                observer.on_compare(base + b, base + a + i);
+                compare(&*v.add(b), &*buf.add(a + i)) != Ordering::Less
+            });
+            for i in 0..count_a {
+                observer.on_write(base + dest + i, base + a + i);
+            }
+            ptr::copy_nonoverlapping(buf.add(a), v.add(dest), count_a);
+            a += count_a;
+            dest += count_a;
+            hole.start = buf.add(a);
+            hole.dest = v.add(dest);
+            if a == mid {
+                break 'outer;
+            }
+
+            // 此时 buf[a] > arr[b]，count_b 至少为 1
+            let count_b = gallop(len - b, false, |i| {
+                observer.on_compare(base + b + i, base + a);
+                compare(&*v.add(b + i), &*buf.add(a)) == Ordering::Less
+            });
+            for i in 0..count_b {
+                observer.on_write(base + dest + i, base + b + i);
+            }
+            ptr::copy(v.add(b), v.add(dest), count_b);
+            b += count_b;
+            dest += count_b;
+            hole.dest = v.add(dest);
+            if b == len {
+                break 'outer;
+            }
+
+            if count_a < MIN_GALLOP && count_b < MIN_GALLOP {
+                *min_gallop += 1;
+                break;
+            }
+            *min_gallop = min_gallop.saturating_sub(1).max(1);
+        }
+    }
+    for offset in 0..mid - a {
+        observer.on_write(base + dest + offset, base + a + offset);
+    }
+    // hole 在此被 drop，把暂存区里剩余的左段元素移回 arr
+}
+
+// 右段较短：把右段移入暂存区，从后往前写回。
+// a 为左段剩余长度，b 为暂存区剩余长度，空洞为 arr[a..a + b]
+unsafe fn merge_hi<T, F, O>(
+    arr: &mut [T],
+    mid: usize,
+    buf: *mut T,
+    min_gallop: &mut usize,
+    compare: &mut F,
+    observer: &mut O,
+    base: usize,
+) where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver + ?Sized,
+{
+    let len = arr.len();
+    let v = arr.as_mut_ptr();
+    ptr::copy_nonoverlapping(v.add(mid), buf, len - mid);
+    let (mut a, mut b) = (mid, len - mid);
+    let mut hole = MergeHole {
+        start: buf,
+        end: buf.add(b),
+        dest: v.add(a),
+    };
+
+    'outer: while a > 0 && b > 0 {
+        let (mut wins_a, mut wins_b) = (0, 0);
+        while a > 0 && b > 0 {
+            let dest = a + b - 1;
+            observer.on_compare(base + mid + b - 1, base + a - 1);
+            // 相等时先放右段元素，保证稳定性
+            if compare(&*buf.add(b - 1), &*v.add(a - 1)) == Ordering::Less {
+                observer.on_write(base + dest, base + a - 1);
+                ptr::copy_nonoverlapping(v.add(a - 1), v.add(dest), 1);
+                a -= 1;
+                wins_a += 1;
+                wins_b = 0;
+                hole.dest = v.add(a);
+            } else {
+                observer.on_write(base + dest, base + mid + b - 1);
+                ptr::copy_nonoverlapping(buf.add(b - 1), v.add(dest), 1);
+                b -= 1;
+                wins_b += 1;
+                wins_a = 0;
+                hole.end = buf.add(b);
+            }
+            if wins_a >= *min_gallop || wins_b >= *min_gallop {
+                break;
+            }
+        }
+
+        while a > 0 && b > 0 {
+            // 左段末尾大于 buf[b - 1] 的元素整体后移
+            let keep_a = gallop(a, true, |i| {
+                observer.on_compare(base + mid + b - 1, base + i);
+                compare(&*buf.add(b - 1), &*v.add(i)) != Ordering::Less
+            });
+            let count_a = a - keep_a;
+            for i in 0..count_a {
+                observer.on_write(base + keep_a + b + i, base + keep_a + i);
+            }
+            ptr::copy(v.add(keep_a), v.add(keep_a + b), count_a);
+            a = keep_a;
+            hole.dest = v.add(a);
+            if a == 0 {
+                break 'outer;
+            }
+
+            // 此时 arr[a - 1] <= buf[b - 1]，count_b 至少为 1
+            let keep_b = gallop(b, true, |i| {
+                observer.on_compare(base + mid + i, base + a - 1);
+                compare(&*buf.add(i), &*v.add(a - 1)) == Ordering::Less
+            });
+            let count_b = b - keep_b;
+            for i in 0..count_b {
+                observer.on_write(base + a + keep_b + i, base + mid + keep_b + i);
+            }
+            ptr::copy_nonoverlapping(buf.add(keep_b), v.add(a + keep_b), count_b);
+            b = keep_b;
+            hole.end = buf.add(b);
+            if b == 0 {
+                break 'outer;
+            }
+
+            if count_a < MIN_GALLOP && count_b < MIN_GALLOP {
+                *min_gallop += 1;
+                break;
+            }
+            *min_gallop = min_gallop.saturating_sub(1).max(1);
+        }
+    }
+    for offset in 0..b {
+        observer.on_write(base + a + offset, base + mid + offset);
+    }
+    // hole 在此被 drop，把暂存区里剩余的右段元素移到 arr[a..a + b]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sort::instrument::SortStats;
+    use crate::sort::merge_sort::merge_sort_observed;
+    use crate::sort::rng::Rng;
+    use std::panic::{self, AssertUnwindSafe};
+
+    #[test]
+    fn test_tim_sort() {
+        let mut arr = vec![64, 34, 25, 12, 22, 11, 90];
+        tim_sort(&mut arr);
+        assert_eq!(arr, vec![11, 12, 22, 25, 34, 64, 90]);
+    }
+
+    #[test]
+    fn test_empty_array() {
+        let mut arr: Vec<i32> = vec![];
+        tim_sort(&mut arr);
+        assert_eq!(arr, Vec::<i32>::new());
+    }
+
+    #[test]
+    fn test_single_element() {
+        let mut arr = vec![1];
+        tim_sort(&mut arr);
+        assert_eq!(arr, vec![1]);
+    }
+
+    #[test]
+    fn test_min_run_length() {
+        assert_eq!(min_run_length(63), 63);
+        assert_eq!(min_run_length(64), 32);
+        assert_eq!(min_run_length(65), 33);
+        assert_eq!(min_run_length(2048), 32);
+        assert_eq!(min_run_length(2049), 33);
+        for n in 64..5000 {
+            let run = min_run_length(n);
+            assert!((32..=64).contains(&run), "{} -> {}", n, run);
+        }
+    }
+
+    #[test]
+    fn test_gallop() {
+        let data = [1, 2, 2, 2, 5, 8, 9];
+        for from_end in [false, true] {
+            assert_eq!(gallop(data.len(), from_end, |i| data[i] <= 2), 4);
+            assert_eq!(gallop(data.len(), from_end, |i| data[i] < 2), 1);
+            assert_eq!(gallop(data.len(), from_end, |i| data[i] < 100), 7);
+            assert_eq!(gallop(data.len(), from_end, |_| false), 0);
+            assert_eq!(gallop(0, from_end, |_| true), 0);
+        }
+    }
+
+    #[test]
+    fn test_random_and_stable() {
+        let mut rng = Rng::new(17);
+        for len in [0, 1, 2, 63, 64, 65, 200, 1000, 5000] {
+            for modulo in [4, 1000, u64::MAX] {
+                // 低位为原始下标，只按高位比较，检查稳定性
+                let mut arr: Vec<(u64, usize)> = (0..len).map(|i| (rng.below(modulo), i)).collect();
+                let mut expected = arr.clone();
+                expected.sort_by_key(|&(k, _)| k);
+                tim_sort_by_key(&mut arr, |&(k, _)| k);
+                assert_eq!(arr, expected, "len {} modulo {}", len, modulo);
+            }
+        }
+    }
+
+    #[test]
+    fn test_nearly_sorted_is_linear() {
+        let n = 100_000usize;
+        let mut sorted: Vec<usize> = (0..n).collect();
+        let mut stats = SortStats::default();
+        tim_sort_observed(&mut sorted, usize::cmp, &mut stats);
+        assert_eq!(stats.comparisons, n - 1);
+        assert_eq!(stats.moves, 0);
+
+        let mut reversed: Vec<usize> = (0..n).rev().collect();
+        let mut stats = SortStats::default();
+        tim_sort_observed(&mut reversed, usize::cmp, &mut stats);
+        assert_eq!(reversed, (0..n).collect::<Vec<_>>());
+        assert_eq!(stats.comparisons, n - 1);
+
+        // 已排序数据后追加少量乱序元素：比较次数仍接近线性
+        let mut rng = Rng::new(5);
+        let mut appended: Vec<usize> = (0..n).collect();
+        appended.extend((0..100).map(|_| rng.below(n as u64) as usize));
+        let mut expected = appended.clone();
+        expected.sort();
+        let mut merge_input = appended.clone();
+
+        let mut tim = SortStats::default();
+        tim_sort_observed(&mut appended, usize::cmp, &mut tim);
+        let mut merge = SortStats::default();
+        merge_sort_observed(&mut merge_input, usize::cmp, &mut merge);
+        assert_eq!(appended, expected);
+        assert_eq!(merge_input, expected);
+        assert!(tim.comparisons < n + n / 10, "{}", tim);
+        assert!(tim.comparisons < merge.comparisons, "tim {} merge {}", tim, merge);
+    }
+
+    #[test]
+    fn test_panic_in_compare_keeps_elements() {
+        let mut rng = Rng::new(23);
+        let original: Vec<String> = (0..500).map(|_| rng.below(1000).to_string()).collect();
+        let mut arr = original.clone();
+        let mut calls = 0;
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            tim_sort_by(&mut arr, |a, b| {
+                calls += 1;
+                if calls == 2000 {
+                    panic!("比较函数出错");
+                }
+                a.cmp(b)
+            });
+        }));
+        assert!(result.is_err());
+        let mut expected = original;
+        expected.sort();
+        arr.sort();
+        assert_eq!(arr, expected);
+    }
+}
@@ -0,0 +1,289 @@
+// 测试特性：事件记录与回放、枚举建模、serde_json::json!宏、文本动画
+// 语法要点：enum TraceEvent { Compare { i, j } }、impl SortObserver、Into<serde_json::Value>
+// 功能：记录排序过程中的每个事件(比较、交换、写入、划分、合并)，
+//       通过回放重建每一步的数组状态，渲染为逐帧文本动画或 JSON
+
+use std::fmt::{self, Display};
+use std::thread;
+use std::time::Duration;
+
+use serde_json::{json, Value};
+
+use super::instrument::SortObserver;
+use super::sorter::Sorter;
+
+// 排序过程中的单个事件，下标相对于整个切片
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraceEvent {
+    Compare { i: usize, j: usize },
+    Swap { i: usize, j: usize },
+    Write { index: usize, from: usize },
+    Alloc { len: usize },
+    Partition { start: usize, lt: usize, gt: usize, end: usize },
+    Merge { start: usize, mid: usize, end: usize },
+}
+
+impl TraceEvent {
+    // 该事件涉及的位置，渲染时高亮显示
+    pub fn positions(&self) -> Vec<usize> {
+        match *self {
+            TraceEvent::Compare { i, j } | TraceEvent::Swap { i, j } => vec![i, j],
+            TraceEvent::Write { index, .. } => vec![index],
+            TraceEvent::Alloc { .. } => Vec::new(),
+            TraceEvent::Partition { lt, gt, .. } => (lt..gt).collect(),
+            TraceEvent::Merge { start, end, .. } => (start..end).collect(),
+        }
+    }
+
+    pub fn to_json(&self) -> Value {
+        match *self {
+            TraceEvent::Compare { i, j } => json!({ "type": "compare", "i": i, "j": j }),
+            TraceEvent::Swap { i, j } => json!({ "type": "swap", "i": i, "j": j }),
+            TraceEvent::Write { index, from } => json!({ "type": "write", "index": index, "from": from }),
+            TraceEvent::Alloc { len } => json!({ "type": "alloc", "len": len }),
+            TraceEvent::Partition { start, lt, gt, end } => {
+                json!({ "type": "partition", "start": start, "lt": lt, "gt": gt, "end": end })
+            }
+            TraceEvent::Merge { start, mid, end } => {
+                json!({ "type": "merge", "start": start, "mid": mid, "end": end })
+            }
+        }
+    }
+}
+
+impl Display for TraceEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            TraceEvent::Compare { i, j } => write!(f, "比较 {} 与 {}", i, j),
+            TraceEvent::Swap { i, j } => write!(f, "交换 {} 与 {}", i, j),
+            TraceEvent::Write { index, from } => write!(f, "写入 {} <- {}", index, from),
+            TraceEvent::Alloc { len } => write!(f, "分配 {} 个元素", len),
+            TraceEvent::Partition { start, lt, gt, end } => {
+                write!(f, "划分 [{}, {}) 主元区间 [{}, {})", start, end, lt, gt)
+            }
+            TraceEvent::Merge { start, mid, end } => {
+                write!(f, "合并 [{}, {}) 与 [{}, {})", start, mid, mid, end)
+            }
+        }
+    }
+}
+
+// 把事件依次记录下来的观察者
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TraceRecorder {
+    pub events: Vec<TraceEvent>,
+}
+
+impl SortObserver for TraceRecorder {
+    fn on_compare(&mut self, i: usize, j: usize) {
+        self.events.push(TraceEvent::Compare { i, j });
+    }
+
+    fn on_swap(&mut self, i: usize, j: usize) {
+        self.events.push(TraceEvent::Swap { i, j });
+    }
+
+    fn on_write(&mut self, index: usize, from: usize) {
+        self.events.push(TraceEvent::Write { index, from });
+    }
+
+    fn on_alloc(&mut self, len: usize) {
+        self.events.push(TraceEvent::Alloc { len });
+    }
+
+    fn on_partition(&mut self, start: usize, lt: usize, gt: usize, end: usize) {
+        self.events.push(TraceEvent::Partition { start, lt, gt, end });
+    }
+
+    fn on_merge(&mut self, start: usize, mid: usize, end: usize) {
+        self.events.push(TraceEvent::Merge { start, mid, end });
+    }
+}
+
+// 一次完整排序的记录：算法名、初始数组和事件序列
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trace<T> {
+    pub algorithm: &'static str,
+    pub initial: Vec<T>,
+    pub events: Vec<TraceEvent>,
+}
+
+// 回放得到的一帧：刚发生的事件以及事件发生后的数组状态
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frame<T> {
+    pub event: TraceEvent,
+    pub state: Vec<T>,
+}
+
+impl<T: Clone> Trace<T> {
+    // 对 input 的拷贝排序并记录全部事件，input 本身不变
+    pub fn record(sorter: &dyn Sorter<T>, input: &[T]) -> Self {
+        let mut recorder = TraceRecorder::default();
+        let mut data = input.to_vec();
+        sorter.sort_observed(&mut data, &mut recorder);
+        Trace {
+            algorithm: sorter.name(),
+            initial: input.to_vec(),
+            events: recorder.events,
+        }
+    }
+
+    // 从初始数组开始回放事件，得到每个事件之后的数组状态。
+    // Write 的来源下标指向当前合并开始时的数组，因此在 Merge 事件处保存一份快照
+    pub fn frames(&self) -> Vec<Frame<T>> {
+        let mut state = self.initial.clone();
+        let mut snapshot = state.clone();
+        let mut frames = Vec::with_capacity(self.events.len());
+        for &event in &self.events {
+            match event {
+                TraceEvent::Swap { i, j } => state.swap(i, j),
+                TraceEvent::Write { index, from } => state[index] = snapshot[from].clone(),
+                TraceEvent::Merge { .. } => snapshot.clone_from(&state),
+                _ => {}
+            }
+            frames.push(Frame {
+                event,
+                state: state.clone(),
+            });
+        }
+        frames
+    }
+
+    // 回放全部事件后的最终数组
+    pub fn final_state(&self) -> Vec<T> {
+        self.frames()
+            .pop()
+            .map_or_else(|| self.initial.clone(), |frame| frame.state)
+    }
+}
+
+impl<T: Clone + Display> Trace<T> {
+    // 每帧一行：序号、事件描述和数组，被涉及的位置用方括号标出
+    pub fn render_frames(&self) -> Vec<String> {
+        let mut frames = vec![format!("{:>5}  {:<28} {}", 0, "初始", render_state(&self.initial, &[]))];
+        for (step, frame) in self.frames().iter().enumerate() {
+            frames.push(format!(
+                "{:>5}  {:<28} {}",
+                step + 1,
+                frame.event.to_string(),
+                render_state(&frame.state, &frame.event.positions())
+            ));
+        }
+        frames
+    }
+
+    pub fn render_text(&self) -> String {
+        let mut text = format!("=== {} ({} 步) ===\n", self.algorithm, self.events.len());
+        for line in self.render_frames() {
+            text.push_str(&line);
+            text.push('\n');
+        }
+        text
+    }
+
+    // 在终端中逐帧播放，每帧之间等待 delay
+    pub fn animate(&self, delay: Duration) {
+        for line in self.render_frames() {
+            print!("\x1b[2J\x1b[H=== {} ===\n{}\n", self.algorithm, line);
+            thread::sleep(delay);
+        }
+    }
+}
+
+impl<T: Clone + Into<Value>> Trace<T> {
+    pub fn to_json(&self) -> Value {
+        let initial: Vec<Value> = self.initial.iter().cloned().map(Into::into).collect();
+        let events: Vec<Value> = self.events.iter().map(TraceEvent::to_json).collect();
+        json!({
+            "algorithm": self.algorithm,
+            "initial": initial,
+            "events": events,
+        })
+    }
+}
+
+fn render_state<T: Display>(state: &[T], highlighted: &[usize]) -> String {
+    state
+        .iter()
+        .enumerate()
+        .map(|(index, value)| {
+            if highlighted.contains(&index) {
+                format!("[{}]", value)
+            } else {
+                format!(" {} ", value)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sort::sorter::{BubbleSort, MergeSort, QuickSort, Registry};
+
+    #[test]
+    fn test_replay_reproduces_every_algorithm() {
+        let input: Vec<u32> = vec![5, 3, 9, 1, 7, 3, 0, 8, 2, 6, 4, 9, 1, 250, 17, 33, 12, 5, 64, 2];
+        let mut expected = input.clone();
+        expected.sort();
+        let registry = Registry::<u32>::all();
+        for sorter in registry.iter() {
+            let trace = Trace::record(sorter, &input);
+            assert_eq!(trace.final_state(), expected, "{}", sorter.name());
+        }
+
+        let floats = vec![0.5, -2.0, 3.25, 0.0, -0.5];
+        let trace = Trace::record(Registry::<f64>::all().get("bucket").unwrap(), &floats);
+        assert_eq!(trace.final_state(), vec![-2.0, -0.5, 0.0, 0.5, 3.25]);
+    }
+
+    #[test]
+    fn test_bubble_trace_events() {
+        let trace = Trace::record(&BubbleSort, &[2, 1, 3]);
+        assert_eq!(
+            trace.events,
+            vec![
+                TraceEvent::Compare { i: 0, j: 1 },
+                TraceEvent::Swap { i: 0, j: 1 },
+                TraceEvent::Compare { i: 1, j: 2 },
+                TraceEvent::Compare { i: 0, j: 1 },
+            ]
+        );
+        assert_eq!(trace.initial, vec![2, 1, 3]);
+    }
+
+    #[test]
+    fn test_merge_and_partition_events() {
+        let input: Vec<u32> = (0..20).rev().collect();
+        let merge = Trace::record(&MergeSort, &input);
+        assert!(merge.events.contains(&TraceEvent::Merge { start: 0, mid: 10, end: 20 }));
+        assert!(merge.events.iter().any(|e| matches!(e, TraceEvent::Write { .. })));
+
+        let input: Vec<u32> = (0..40).rev().collect();
+        let quick = Trace::record(&QuickSort, &input);
+        assert!(quick.events.iter().any(|e| matches!(e, TraceEvent::Partition { start: 0, end: 40, .. })));
+    }
+
+    #[test]
+    fn test_render_text() {
+        let trace = Trace::record(&BubbleSort, &[2, 1]);
+        let text = trace.render_text();
+        let lines: Vec<_> = text.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].contains("bubble"));
+        assert!(lines[2].contains("比较 0 与 1") && lines[2].contains("[2][1]"));
+        assert!(lines[3].contains("交换 0 与 1") && lines[3].contains("[1][2]"));
+    }
+
+    #[test]
+    fn test_to_json() {
+        let trace = Trace::record(&BubbleSort, &[2u32, 1]);
+        let value = trace.to_json();
+        assert_eq!(value["algorithm"], "bubble");
+        assert_eq!(value["initial"], json!([2, 1]));
+        assert_eq!(value["events"][1], json!({ "type": "swap", "i": 0, "j": 1 }));
+        let text = serde_json::to_string(&value).unwrap();
+        assert!(text.contains("\"compare\""));
+    }
+}
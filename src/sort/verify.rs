@@ -0,0 +1,296 @@
+// 测试特性：基于性质的随机测试、稳定性检查、可复现的种子
+// 语法要点：自定义 PartialOrd（只比较键）、impl Fn(&mut [T]) 参数、std::error::Error
+// 功能：检查排序结果是输入的有序排列，对声称稳定的算法检查相等元素的相对顺序，
+//       用内置伪随机数生成器批量产生用例，对每个算法运行成千上万次
+
+use std::cmp::Ordering;
+use std::fmt;
+
+use super::bench::Distribution;
+use super::bucket_sort::bucket_sort_by_key;
+use super::counting_sort::counting_sort_by_key;
+use super::radix_sort::radix_sort_by_key;
+use super::rng::Rng;
+use super::sorter::{Registry, Sorter};
+
+// 排序结果违反的性质
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Violation {
+    // 输出长度与输入不同
+    LengthChanged { expected: usize, actual: usize },
+    // output[index] > output[index + 1]
+    NotSorted { index: usize },
+    // 输出不是输入的一个排列（元素丢失、重复或被改写）
+    NotPermutation,
+    // 输出中 index 与 index + 1 的键相等，但原始顺序被颠倒
+    Unstable { index: usize },
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::LengthChanged { expected, actual } => {
+                write!(f, "输出长度 {} 与输入长度 {} 不同", actual, expected)
+            }
+            Violation::NotSorted { index } => write!(f, "位置 {} 与 {} 的元素逆序", index, index + 1),
+            Violation::NotPermutation => write!(f, "输出不是输入的排列"),
+            Violation::Unstable { index } => {
+                write!(f, "位置 {} 与 {} 的相等元素交换了原始顺序", index, index + 1)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Violation {}
+
+// 用于稳定性检查的记录：只按 key 比较，index 保存它在输入中的原始位置
+#[derive(Debug, Clone, Copy)]
+pub struct Tagged {
+    pub key: u32,
+    pub index: usize,
+}
+
+impl PartialEq for Tagged {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl PartialOrd for Tagged {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.key.partial_cmp(&other.key)
+    }
+}
+
+pub fn check_sorted_by<T, F>(arr: &[T], mut compare: F) -> Result<(), Violation>
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    match arr.windows(2).position(|w| compare(&w[0], &w[1]) == Ordering::Greater) {
+        Some(index) => Err(Violation::NotSorted { index }),
+        None => Ok(()),
+    }
+}
+
+// 两份拷贝分别用标准库排序后逐元素比较
+pub fn check_permutation<T: Ord + Clone>(input: &[T], output: &[T]) -> Result<(), Violation> {
+    if input.len() != output.len() {
+        return Err(Violation::LengthChanged {
+            expected: input.len(),
+            actual: output.len(),
+        });
+    }
+    let mut expected = input.to_vec();
+    let mut actual = output.to_vec();
+    expected.sort_unstable();
+    actual.sort_unstable();
+    if expected == actual {
+        Ok(())
+    } else {
+        Err(Violation::NotPermutation)
+    }
+}
+
+// 键相等的相邻记录必须保持原始下标递增
+pub fn check_stable(output: &[Tagged]) -> Result<(), Violation> {
+    match output
+        .windows(2)
+        .position(|w| w[0].key == w[1].key && w[0].index > w[1].index)
+    {
+        Some(index) => Err(Violation::Unstable { index }),
+        None => Ok(()),
+    }
+}
+
+// 对 input 的拷贝排序，检查结果有序且是输入的排列
+pub fn verify_sorter<T: Ord + Clone>(sorter: &dyn Sorter<T>, input: &[T]) -> Result<(), Violation> {
+    let mut output = input.to_vec();
+    sorter.sort(&mut output);
+    check_permutation(input, &output)?;
+    check_sorted_by(&output, T::cmp)
+}
+
+// 给每个键附上原始下标后排序，检查有序、排列和稳定性
+pub fn verify_stable<F>(sort: F, keys: &[u32]) -> Result<(), Violation>
+where
+    F: FnOnce(&mut [Tagged]),
+{
+    let mut records: Vec<Tagged> = keys.iter().zip(0..).map(|(&key, index)| Tagged { key, index }).collect();
+    sort(&mut records);
+
+    let mut seen = vec![false; keys.len()];
+    if records.len() != keys.len() {
+        return Err(Violation::LengthChanged {
+            expected: keys.len(),
+            actual: records.len(),
+        });
+    }
+    for record in &records {
+        // 每个原始下标恰好出现一次，且键未被改写
+        if record.index >= keys.len() || seen[record.index] || keys[record.index] != record.key {
+            return Err(Violation::NotPermutation);
+        }
+        seen[record.index] = true;
+    }
+    check_sorted_by(&records, |a, b| a.key.cmp(&b.key))?;
+    check_stable(&records)
+}
+
+// 随机测试配置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PropertyConfig {
+    pub cases: usize,
+    pub max_len: usize,
+    pub seed: u64,
+}
+
+impl Default for PropertyConfig {
+    fn default() -> Self {
+        PropertyConfig {
+            cases: 2_000,
+            max_len: 100,
+            seed: 0x5eed,
+        }
+    }
+}
+
+// 一个失败用例：算法名、第几个用例、输入数据和违反的性质
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Failure {
+    pub algorithm: &'static str,
+    pub case: usize,
+    pub input: Vec<u32>,
+    pub violation: Violation,
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} 用例 #{}: {} 输入 {:?}", self.algorithm, self.case, self.violation, self.input)
+    }
+}
+
+// 生成第 case 个用例：长度、分布和取值范围都随机，
+// 取值范围小时会产生大量重复键，便于暴露稳定性问题
+pub fn generate_case(rng: &mut Rng, max_len: usize) -> Vec<u32> {
+    let len = rng.below(max_len as u64 + 1) as usize;
+    let distribution = Distribution::ALL[rng.below(Distribution::ALL.len() as u64) as usize];
+    let mut data = distribution.generate(len, rng);
+    match rng.below(3) {
+        0 => {}
+        1 => {
+            let modulo = rng.below(len as u64 + 1) as u32 + 1;
+            data.iter_mut().for_each(|x| *x %= modulo);
+        }
+        _ => data.iter_mut().for_each(|x| *x %= 4),
+    }
+    data
+}
+
+// 对注册表中的每个算法运行 config.cases 个随机用例，
+// 并对所有声称稳定的算法检查稳定性，返回全部失败用例
+pub fn run_properties(config: &PropertyConfig) -> Vec<Failure> {
+    let registry = Registry::<u32>::all();
+    let stable_comparison: Vec<_> = Registry::<Tagged>::comparison()
+        .iter()
+        .filter(|s| s.is_stable())
+        .map(|s| s.name())
+        .collect();
+    let tagged = Registry::<Tagged>::comparison();
+
+    let mut rng = Rng::new(config.seed);
+    let mut failures = Vec::new();
+    for case in 0..config.cases {
+        let input = generate_case(&mut rng, config.max_len);
+        let mut record = |algorithm: &'static str, result: Result<(), Violation>| {
+            if let Err(violation) = result {
+                failures.push(Failure {
+                    algorithm,
+                    case,
+                    input: input.clone(),
+                    violation,
+                });
+            }
+        };
+
+        for sorter in registry.iter() {
+            record(sorter.name(), verify_sorter(sorter, &input));
+        }
+        for &name in &stable_comparison {
+            let sorter = tagged.get(name).unwrap();
+            record(name, verify_stable(|arr| sorter.sort(arr), &input));
+        }
+        record("radix", verify_stable(|arr| radix_sort_by_key(arr, |t| t.key), &input));
+        // 与 CountingSort 一致：取值范围过大时退回基数排序
+        let counting = verify_stable(
+            |arr| {
+                if counting_sort_by_key(arr, |t| t.key).is_err() {
+                    radix_sort_by_key(arr, |t| t.key);
+                }
+            },
+            &input,
+        );
+        record("counting", counting);
+        record("bucket", verify_stable(|arr| bucket_sort_by_key(arr, |t| t.key as f64), &input));
+    }
+    failures
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sort::sorter::{QuickSort, SelectionSort};
+
+    #[test]
+    fn test_every_algorithm_passes() {
+        let failures = run_properties(&PropertyConfig::default());
+        let report: Vec<String> = failures.iter().take(5).map(|f| f.to_string()).collect();
+        assert!(failures.is_empty(), "{}", report.join("\n"));
+    }
+
+    #[test]
+    fn test_larger_inputs() {
+        let config = PropertyConfig {
+            cases: 20,
+            max_len: 3_000,
+            seed: 99,
+        };
+        let mut rng = Rng::new(config.seed);
+        for _ in 0..config.cases {
+            let input = generate_case(&mut rng, config.max_len);
+            assert_eq!(verify_sorter(&QuickSort, &input), Ok(()));
+        }
+    }
+
+    #[test]
+    fn test_detects_violations() {
+        assert_eq!(check_sorted_by(&[1, 3, 2], u32::cmp), Err(Violation::NotSorted { index: 1 }));
+        assert_eq!(check_permutation(&[1, 2, 2], &[1, 1, 2]), Err(Violation::NotPermutation));
+        assert_eq!(
+            check_permutation(&[1, 2], &[1]),
+            Err(Violation::LengthChanged { expected: 2, actual: 1 })
+        );
+
+        // 选择排序会把第一个 1 换到末尾，破坏稳定性
+        let sorter: &dyn Sorter<Tagged> = &SelectionSort;
+        assert_eq!(
+            verify_stable(|arr| sorter.sort(arr), &[1, 1, 0]),
+            Err(Violation::Unstable { index: 1 })
+        );
+        // 改写了键的“排序”不是排列
+        assert_eq!(
+            verify_stable(|arr| arr.iter_mut().for_each(|t| t.key = 0), &[2, 1]),
+            Err(Violation::NotPermutation)
+        );
+    }
+
+    #[test]
+    fn test_generate_case_is_reproducible() {
+        let mut a = Rng::new(3);
+        let mut b = Rng::new(3);
+        for _ in 0..50 {
+            let case = generate_case(&mut a, 40);
+            assert!(case.len() <= 40);
+            assert_eq!(case, generate_case(&mut b, 40));
+        }
+    }
+}
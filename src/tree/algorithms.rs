@@ -0,0 +1,399 @@
+// 测试特性：二叉树算法(高度、直径、最近公共祖先、平衡/对称/BST 判定、路径和、镜像、子树匹配)
+// 语法要点：Option<&Node<T>> 表示可能为空的树、显式栈模拟后序遍历、前缀和 + HashMap、trait 约束 Add/Sub/Hash
+// 功能：面向教学和面试练习的二叉树分析函数库；所有函数都用显式栈或队列实现，
+//       退化成链表的深树也不会栈溢出
+
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+use std::mem;
+use std::ops::{Add, Sub};
+
+use super::{view, Node};
+
+// Combines the results of the left and right subtrees bottom-up, like a recursive
+// post-order function would, but with an explicit stack. `f` receives `None` for a
+// missing child.
+pub fn fold_postorder<'a, T, R, F>(root: &'a Node<T>, mut f: F) -> R
+where
+    F: FnMut(&'a Node<T>, Option<R>, Option<R>) -> R,
+{
+    // The flag records whether the node's children have already been pushed.
+    let mut stack = vec![(root, false)];
+    let mut done: Vec<R> = Vec::new();
+    while let Some((node, expanded)) = stack.pop() {
+        if !expanded {
+            stack.push((node, true));
+            stack.extend(node.right.as_deref().map(|n| (n, false)));
+            stack.extend(node.left.as_deref().map(|n| (n, false)));
+            continue;
+        }
+        // The right subtree finished last, so its result is on top.
+        let right = node.right.as_ref().and_then(|_| done.pop());
+        let left = node.left.as_ref().and_then(|_| done.pop());
+        done.push(f(node, left, right));
+    }
+    done.pop().expect("the root is always finished last")
+}
+
+// Number of nodes on the longest root-to-leaf path; 0 for an empty tree.
+pub fn height<T>(root: Option<&Node<T>>) -> usize {
+    view::height(root)
+}
+
+pub fn size<T>(root: Option<&Node<T>>) -> usize {
+    root.map_or(0, |node| node.iter_preorder().count())
+}
+
+// Number of edges on the longest path between any two nodes.
+pub fn diameter<T>(root: Option<&Node<T>>) -> usize {
+    let Some(root) = root else { return 0 };
+    // Each subtree yields (height, diameter).
+    let (_, diameter) = fold_postorder(root, |_, left: Option<(usize, usize)>, right| {
+        let (left_height, left_diameter) = left.unwrap_or((0, 0));
+        let (right_height, right_diameter) = right.unwrap_or((0, 0));
+        let through = left_height + right_height;
+        (
+            1 + left_height.max(right_height),
+            through.max(left_diameter).max(right_diameter),
+        )
+    });
+    diameter
+}
+
+// The path of nodes from the root to the first node (in preorder) matching `target`.
+fn path_to<'a, T: PartialEq>(root: &'a Node<T>, target: &T) -> Option<Vec<&'a Node<T>>> {
+    let mut path: Vec<&Node<T>> = Vec::new();
+    let mut stack = vec![(root, 0)];
+    while let Some((node, depth)) = stack.pop() {
+        path.truncate(depth);
+        path.push(node);
+        if node.value == *target {
+            return Some(path);
+        }
+        stack.extend(node.right.as_deref().map(|n| (n, depth + 1)));
+        stack.extend(node.left.as_deref().map(|n| (n, depth + 1)));
+    }
+    None
+}
+
+// The deepest node having both `a` and `b` as descendants (a node is its own
+// descendant), or `None` if either value is missing.
+pub fn lowest_common_ancestor<'a, T: PartialEq>(root: Option<&'a Node<T>>, a: &T, b: &T) -> Option<&'a Node<T>> {
+    let root = root?;
+    let path_a = path_to(root, a)?;
+    let path_b = path_to(root, b)?;
+    path_a
+        .iter()
+        .zip(&path_b)
+        .take_while(|(x, y)| std::ptr::eq(**x, **y))
+        .last()
+        .map(|(node, _)| *node)
+}
+
+// Whether the heights of the two subtrees of every node differ by at most one.
+pub fn is_balanced<T>(root: Option<&Node<T>>) -> bool {
+    let Some(root) = root else { return true };
+    // Each subtree yields its height, or None once an imbalance has been found.
+    fold_postorder(root, |_, left: Option<Option<usize>>, right| {
+        let left = left.unwrap_or(Some(0))?;
+        let right = right.unwrap_or(Some(0))?;
+        (left.abs_diff(right) <= 1).then_some(1 + left.max(right))
+    })
+    .is_some()
+}
+
+// Whether the tree is a mirror image of itself around the root.
+pub fn is_symmetric<T: PartialEq>(root: Option<&Node<T>>) -> bool {
+    let Some(root) = root else { return true };
+    let mut pairs = VecDeque::from([(root.left.as_deref(), root.right.as_deref())]);
+    while let Some(pair) = pairs.pop_front() {
+        match pair {
+            (None, None) => {}
+            (Some(a), Some(b)) if a.value == b.value => {
+                pairs.push_back((a.left.as_deref(), b.right.as_deref()));
+                pairs.push_back((a.right.as_deref(), b.left.as_deref()));
+            }
+            _ => return false,
+        }
+    }
+    true
+}
+
+// Whether the in-order sequence is strictly increasing, i.e. every node is greater
+// than everything in its left subtree and less than everything in its right subtree.
+pub fn is_bst<T: Ord>(root: Option<&Node<T>>) -> bool {
+    let Some(root) = root else { return true };
+    let mut values = root.iter_inorder();
+    let Some(mut previous) = values.next() else { return true };
+    for value in values {
+        if previous >= value {
+            return false;
+        }
+        previous = value;
+    }
+    true
+}
+
+// Every root-to-leaf path, left to right.
+pub fn root_to_leaf_paths<T>(root: Option<&Node<T>>) -> Vec<Vec<&T>> {
+    let mut paths = Vec::new();
+    let mut path: Vec<&T> = Vec::new();
+    let mut stack: Vec<(&Node<T>, usize)> = root.map(|node| (node, 0)).into_iter().collect();
+    while let Some((node, depth)) = stack.pop() {
+        path.truncate(depth);
+        path.push(&node.value);
+        if node.left.is_none() && node.right.is_none() {
+            paths.push(path.clone());
+        }
+        stack.extend(node.right.as_deref().map(|n| (n, depth + 1)));
+        stack.extend(node.left.as_deref().map(|n| (n, depth + 1)));
+    }
+    paths
+}
+
+// Root-to-leaf paths whose values add up to `target`. `T::default()` is the zero.
+pub fn path_sum<T>(root: Option<&Node<T>>, target: T) -> Vec<Vec<&T>>
+where
+    T: Copy + Add<Output = T> + Default + PartialEq,
+{
+    root_to_leaf_paths(root)
+        .into_iter()
+        .filter(|path| path.iter().fold(T::default(), |sum, &&value| sum + value) == target)
+        .collect()
+}
+
+pub fn has_path_sum<T>(root: Option<&Node<T>>, target: T) -> bool
+where
+    T: Copy + Add<Output = T> + Default + PartialEq,
+{
+    !path_sum(root, target).is_empty()
+}
+
+// Number of downward paths (starting and ending anywhere) whose values add up to
+// `target`. A path from u down to v sums to prefix(v) - prefix(parent of u), so each
+// node counts the ancestors' prefix sums equal to prefix(node) - target.
+pub fn count_path_sums<T>(root: Option<&Node<T>>, target: T) -> usize
+where
+    T: Copy + Add<Output = T> + Sub<Output = T> + Default + Eq + Hash,
+{
+    enum Step<'a, T> {
+        Enter(&'a Node<T>, T),
+        // Leaving a node removes its prefix sum from the ancestors.
+        Exit(T),
+    }
+
+    let mut prefixes: HashMap<T, usize> = HashMap::from([(T::default(), 1)]);
+    let mut count = 0;
+    let mut stack: Vec<Step<T>> = root.map(|node| Step::Enter(node, T::default())).into_iter().collect();
+    while let Some(step) = stack.pop() {
+        match step {
+            Step::Enter(node, above) => {
+                let sum = above + node.value;
+                count += prefixes.get(&(sum - target)).copied().unwrap_or(0);
+                *prefixes.entry(sum).or_insert(0) += 1;
+                stack.push(Step::Exit(sum));
+                stack.extend(node.right.as_deref().map(|n| Step::Enter(n, sum)));
+                stack.extend(node.left.as_deref().map(|n| Step::Enter(n, sum)));
+            }
+            Step::Exit(sum) => {
+                if let Some(seen) = prefixes.get_mut(&sum) {
+                    *seen -= 1;
+                }
+            }
+        }
+    }
+    count
+}
+
+// Swaps the children of every node in place (a.k.a. invert).
+pub fn mirror<T>(root: &mut Node<T>) {
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        mem::swap(&mut node.left, &mut node.right);
+        stack.extend(node.left.as_deref_mut());
+        stack.extend(node.right.as_deref_mut());
+    }
+}
+
+// Whether both trees have the same shape and the same values.
+pub fn is_same_tree<T: PartialEq>(a: Option<&Node<T>>, b: Option<&Node<T>>) -> bool {
+    let mut pairs = vec![(a, b)];
+    while let Some(pair) = pairs.pop() {
+        match pair {
+            (None, None) => {}
+            (Some(a), Some(b)) if a.value == b.value => {
+                pairs.push((a.left.as_deref(), b.left.as_deref()));
+                pairs.push((a.right.as_deref(), b.right.as_deref()));
+            }
+            _ => return false,
+        }
+    }
+    true
+}
+
+// Whether `sub` equals the complete subtree rooted at some node of `root`.
+// The empty tree is a subtree of every tree.
+pub fn is_subtree<T: PartialEq>(root: Option<&Node<T>>, sub: Option<&Node<T>>) -> bool {
+    let Some(sub) = sub else { return true };
+    root.is_some_and(|root| {
+        root.iter_preorder_nodes()
+            .any(|node| node.value == sub.value && is_same_tree(Some(node), Some(sub)))
+    })
+}
+
+impl<T> Node<T> {
+    // Preorder over the nodes themselves rather than their values.
+    fn iter_preorder_nodes(&self) -> impl Iterator<Item = &Node<T>> {
+        let mut stack = vec![self];
+        std::iter::from_fn(move || {
+            let node = stack.pop()?;
+            stack.extend(node.right.as_deref());
+            stack.extend(node.left.as_deref());
+            Some(node)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tree::left_chain;
+
+    fn tree(values: &[Option<i32>]) -> Option<Box<Node<i32>>> {
+        Node::from_level_order(values.iter().copied()).unwrap()
+    }
+
+    //        5
+    //      /   \
+    //     4     8
+    //    /     / \
+    //   11    13  4
+    //  /  \      / \
+    // 7    2    5   1
+    fn sample() -> Option<Box<Node<i32>>> {
+        tree(&[
+            Some(5), Some(4), Some(8), Some(11), None, Some(13), Some(4), Some(7), Some(2), None, None, Some(5), Some(1),
+        ])
+    }
+
+    #[test]
+    fn test_height_size_diameter() {
+        let t = sample();
+        assert_eq!(height(t.as_deref()), 4);
+        assert_eq!(size(t.as_deref()), 10);
+        // 7 - 11 - 4 - 5 - 8 - 4 - 5
+        assert_eq!(diameter(t.as_deref()), 6);
+
+        // The longest path need not pass through the root.
+        let t = tree(&[Some(1), Some(2), None, Some(3), Some(4), Some(5), None, None, Some(6), Some(7), None, None, Some(8)]);
+        assert_eq!(diameter(t.as_deref()), 6);
+
+        assert_eq!(height::<i32>(None), 0);
+        assert_eq!(size::<i32>(None), 0);
+        assert_eq!(diameter::<i32>(None), 0);
+        assert_eq!(diameter(Some(&Node::new(1))), 0);
+    }
+
+    #[test]
+    fn test_lowest_common_ancestor() {
+        let t = sample();
+        let lca = |a, b| lowest_common_ancestor(t.as_deref(), &a, &b).map(|node| node.value);
+        assert_eq!(lca(7, 2), Some(11));
+        assert_eq!(lca(7, 13), Some(5));
+        assert_eq!(lca(13, 1), Some(8));
+        assert_eq!(lca(11, 2), Some(11));
+        assert_eq!(lca(2, 2), Some(2));
+        assert_eq!(lca(7, 99), None);
+        assert!(lowest_common_ancestor::<i32>(None, &1, &1).is_none());
+    }
+
+    #[test]
+    fn test_is_balanced() {
+        assert!(is_balanced(tree(&[Some(3), Some(9), Some(20), None, None, Some(15), Some(7)]).as_deref()));
+        assert!(!is_balanced(tree(&[Some(1), Some(2), Some(2), Some(3), Some(3), None, None, Some(4), Some(4)]).as_deref()));
+        // Both subtrees of the root have height 2, but each of them is a chain.
+        assert!(!is_balanced(tree(&[Some(1), Some(2), Some(2), Some(3), None, None, Some(3), Some(4), None, None, Some(4)]).as_deref()));
+        assert!(is_balanced::<i32>(None));
+    }
+
+    #[test]
+    fn test_is_symmetric() {
+        assert!(is_symmetric(tree(&[Some(1), Some(2), Some(2), Some(3), Some(4), Some(4), Some(3)]).as_deref()));
+        assert!(!is_symmetric(tree(&[Some(1), Some(2), Some(2), None, Some(3), None, Some(3)]).as_deref()));
+        assert!(is_symmetric(Some(&Node::new(1))));
+        assert!(is_symmetric::<i32>(None));
+    }
+
+    #[test]
+    fn test_is_bst() {
+        assert!(is_bst(tree(&[Some(2), Some(1), Some(3)]).as_deref()));
+        // 3 is in the right subtree of 5 but smaller than it.
+        assert!(!is_bst(tree(&[Some(5), Some(1), Some(6), None, None, Some(3), Some(7)]).as_deref()));
+        assert!(!is_bst(tree(&[Some(2), Some(2), Some(2)]).as_deref()));
+        assert!(is_bst::<i32>(None));
+    }
+
+    #[test]
+    fn test_paths_and_sums() {
+        let t = sample();
+        let paths = root_to_leaf_paths(t.as_deref());
+        assert_eq!(
+            paths,
+            vec![vec![&5, &4, &11, &7], vec![&5, &4, &11, &2], vec![&5, &8, &13], vec![&5, &8, &4, &5], vec![&5, &8, &4, &1]]
+        );
+        assert_eq!(path_sum(t.as_deref(), 22), vec![vec![&5, &4, &11, &2], vec![&5, &8, &4, &5]]);
+        assert!(has_path_sum(t.as_deref(), 26));
+        assert!(!has_path_sum(t.as_deref(), 5));
+        assert!(!has_path_sum::<i32>(None, 0));
+
+        // 5-4-11-2, 5-8-4-5 and 4-11-7, which starts below the root.
+        assert_eq!(count_path_sums(t.as_deref(), 22), 3);
+        let t = tree(&[Some(10), Some(5), Some(-3), Some(3), Some(2), None, Some(11), Some(3), Some(-2), None, Some(1)]);
+        assert_eq!(count_path_sums(t.as_deref(), 8), 3);
+    }
+
+    #[test]
+    fn test_mirror() {
+        let mut t = tree(&[Some(4), Some(2), Some(7), Some(1), Some(3), Some(6), Some(9)]).unwrap();
+        mirror(&mut t);
+        assert_eq!(
+            t.to_level_order(),
+            vec![Some(4), Some(7), Some(2), Some(9), Some(6), Some(3), Some(1)]
+        );
+        let original = tree(&[Some(4), Some(2), Some(7), Some(1), Some(3), Some(6), Some(9)]);
+        assert!(!is_same_tree(Some(&t), original.as_deref()));
+        mirror(&mut t);
+        assert!(is_same_tree(Some(&t), original.as_deref()));
+    }
+
+    #[test]
+    fn test_subtree_equality() {
+        let t = tree(&[Some(3), Some(4), Some(5), Some(1), Some(2)]);
+        assert!(is_subtree(t.as_deref(), tree(&[Some(4), Some(1), Some(2)]).as_deref()));
+        // Matching values are not enough: the whole subtree must match.
+        let t2 = tree(&[Some(3), Some(4), Some(5), Some(1), Some(2), None, None, None, None, Some(0)]);
+        assert!(!is_subtree(t2.as_deref(), tree(&[Some(4), Some(1), Some(2)]).as_deref()));
+        assert!(is_subtree(t.as_deref(), None));
+        assert!(!is_subtree(None, Some(&Node::new(1))));
+        assert!(is_same_tree::<i32>(None, None));
+        assert!(!is_same_tree(tree(&[Some(1), Some(2)]).as_deref(), tree(&[Some(1), None, Some(2)]).as_deref()));
+    }
+
+    #[test]
+    fn test_deep_chain() {
+        // The values along the chain still add up to less than i32::MAX.
+        let n = 50_000;
+        let mut chain = left_chain(n);
+        assert_eq!(height(Some(&chain)), n as usize);
+        assert_eq!(diameter(Some(&chain)), n as usize - 1);
+        assert!(!is_balanced(Some(&chain)));
+        // Each node is larger than its left child, so the chain is a valid BST.
+        assert!(is_bst(Some(&chain)));
+        assert_eq!(lowest_common_ancestor(Some(&chain), &0, &1).map(|node| node.value), Some(1));
+        assert_eq!(count_path_sums(Some(&chain), 0), 1);
+        mirror(&mut chain);
+        assert!(chain.left.is_none());
+        assert!(!is_bst(Some(&chain)));
+        assert_eq!(chain.iter_inorder().next(), Some(&(n - 1)));
+    }
+}
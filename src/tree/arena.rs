@@ -0,0 +1,625 @@
+// 测试特性：基于 arena(Vec) 的树、类型化句柄 NodeId、父指针与兄弟链表、代数(generation)防止悬垂句柄
+// 语法要点：#[derive(Copy)] 新类型句柄、impl Index/IndexMut、DoubleEndedIterator、自定义错误类型
+// 功能：所有节点存放在一个 Vec 中，通过 NodeId 相互引用：支持访问父节点、O(1) 访问兄弟节点、
+//       任意多个子节点、摘下/重新挂接子树；既不需要 Rc<RefCell<…>> 的运行时借用检查，
+//       也不会像 Box 链那样在析构深树时递归
+
+use std::error::Error;
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+// A handle to a node in an `ArenaTree`. The generation makes handles to removed
+// nodes detectably stale even after their slot has been reused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NodeId {
+    index: usize,
+    generation: u32,
+}
+
+impl NodeId {
+    pub fn index(self) -> usize {
+        self.index
+    }
+}
+
+impl fmt::Display for NodeId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{}v{}", self.index, self.generation)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArenaError {
+    // The id was never issued by this arena or its node has been removed.
+    InvalidId(NodeId),
+    // Only detached subtree roots can be attached somewhere.
+    NotDetached(NodeId),
+    // Sibling insertion needs the sibling to have a parent.
+    IsRoot(NodeId),
+    // The new parent lies inside the subtree being attached.
+    WouldCreateCycle { parent: NodeId, child: NodeId },
+}
+
+impl fmt::Display for ArenaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArenaError::InvalidId(id) => write!(f, "node {} does not exist", id),
+            ArenaError::NotDetached(id) => write!(f, "node {} already has a parent", id),
+            ArenaError::IsRoot(id) => write!(f, "node {} has no parent", id),
+            ArenaError::WouldCreateCycle { parent, child } => {
+                write!(f, "attaching {} under {} would create a cycle", child, parent)
+            }
+        }
+    }
+}
+
+impl Error for ArenaError {}
+
+#[derive(Debug, Clone)]
+struct ArenaNode<T> {
+    value: T,
+    parent: Option<NodeId>,
+    first_child: Option<NodeId>,
+    last_child: Option<NodeId>,
+    prev_sibling: Option<NodeId>,
+    next_sibling: Option<NodeId>,
+}
+
+#[derive(Debug, Clone)]
+struct Slot<T> {
+    generation: u32,
+    node: Option<ArenaNode<T>>,
+}
+
+// An n-ary forest stored in a Vec. Children form a doubly linked sibling list,
+// so parent, first/last child and both siblings are all reachable in O(1).
+#[derive(Debug, Clone)]
+pub struct ArenaTree<T> {
+    slots: Vec<Slot<T>>,
+    // Indices of empty slots, reused before the Vec grows.
+    free: Vec<usize>,
+    len: usize,
+}
+
+impl<T> ArenaTree<T> {
+    pub fn new() -> Self {
+        ArenaTree {
+            slots: Vec::new(),
+            free: Vec::new(),
+            len: 0,
+        }
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        ArenaTree {
+            slots: Vec::with_capacity(capacity),
+            free: Vec::new(),
+            len: 0,
+        }
+    }
+
+    // Number of live nodes.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    // Adds a detached node, which is the root of its own one-node tree.
+    pub fn new_node(&mut self, value: T) -> NodeId {
+        let node = ArenaNode {
+            value,
+            parent: None,
+            first_child: None,
+            last_child: None,
+            prev_sibling: None,
+            next_sibling: None,
+        };
+        self.len += 1;
+        if let Some(index) = self.free.pop() {
+            let slot = &mut self.slots[index];
+            slot.node = Some(node);
+            return NodeId {
+                index,
+                generation: slot.generation,
+            };
+        }
+        self.slots.push(Slot {
+            generation: 0,
+            node: Some(node),
+        });
+        NodeId {
+            index: self.slots.len() - 1,
+            generation: 0,
+        }
+    }
+
+    pub fn contains(&self, id: NodeId) -> bool {
+        self.node(id).is_ok()
+    }
+
+    pub fn get(&self, id: NodeId) -> Option<&T> {
+        self.node(id).ok().map(|node| &node.value)
+    }
+
+    pub fn get_mut(&mut self, id: NodeId) -> Option<&mut T> {
+        self.node_mut(id).ok().map(|node| &mut node.value)
+    }
+
+    fn node(&self, id: NodeId) -> Result<&ArenaNode<T>, ArenaError> {
+        self.slots
+            .get(id.index)
+            .filter(|slot| slot.generation == id.generation)
+            .and_then(|slot| slot.node.as_ref())
+            .ok_or(ArenaError::InvalidId(id))
+    }
+
+    fn node_mut(&mut self, id: NodeId) -> Result<&mut ArenaNode<T>, ArenaError> {
+        self.slots
+            .get_mut(id.index)
+            .filter(|slot| slot.generation == id.generation)
+            .and_then(|slot| slot.node.as_mut())
+            .ok_or(ArenaError::InvalidId(id))
+    }
+
+    // For ids taken from the links of a live node, which are always valid.
+    fn linked_mut(&mut self, id: NodeId) -> &mut ArenaNode<T> {
+        self.node_mut(id).expect("links always point to live nodes")
+    }
+
+    // Navigation returns `None` both for a missing relative and for an invalid id.
+
+    pub fn parent(&self, id: NodeId) -> Option<NodeId> {
+        self.node(id).ok()?.parent
+    }
+
+    pub fn first_child(&self, id: NodeId) -> Option<NodeId> {
+        self.node(id).ok()?.first_child
+    }
+
+    pub fn last_child(&self, id: NodeId) -> Option<NodeId> {
+        self.node(id).ok()?.last_child
+    }
+
+    pub fn prev_sibling(&self, id: NodeId) -> Option<NodeId> {
+        self.node(id).ok()?.prev_sibling
+    }
+
+    pub fn next_sibling(&self, id: NodeId) -> Option<NodeId> {
+        self.node(id).ok()?.next_sibling
+    }
+
+    pub fn is_root(&self, id: NodeId) -> bool {
+        self.node(id).is_ok_and(|node| node.parent.is_none())
+    }
+
+    // Number of edges between the node and the root of its tree.
+    pub fn depth(&self, id: NodeId) -> usize {
+        self.ancestors(id).count()
+    }
+
+    pub fn children(&self, id: NodeId) -> Children<'_, T> {
+        let node = self.node(id).ok();
+        Children {
+            tree: self,
+            front: node.and_then(|n| n.first_child),
+            back: node.and_then(|n| n.last_child),
+        }
+    }
+
+    // The parent, grandparent, ... up to the root; excludes the node itself.
+    pub fn ancestors(&self, id: NodeId) -> Ancestors<'_, T> {
+        Ancestors {
+            tree: self,
+            next: self.parent(id),
+        }
+    }
+
+    // The node and everything below it in preorder, using the links instead of a stack.
+    pub fn descendants(&self, id: NodeId) -> Descendants<'_, T> {
+        Descendants {
+            tree: self,
+            root: id,
+            next: self.contains(id).then_some(id),
+        }
+    }
+
+    // Adds `child`, which must be a detached root, as the last child of `parent`.
+    pub fn append_child(&mut self, parent: NodeId, child: NodeId) -> Result<(), ArenaError> {
+        self.check_attachable(parent, child)?;
+        let last = self.node(parent)?.last_child;
+        self.link(child, parent, last, None);
+        Ok(())
+    }
+
+    // Adds `child`, which must be a detached root, as the first child of `parent`.
+    pub fn prepend_child(&mut self, parent: NodeId, child: NodeId) -> Result<(), ArenaError> {
+        self.check_attachable(parent, child)?;
+        let first = self.node(parent)?.first_child;
+        self.link(child, parent, None, first);
+        Ok(())
+    }
+
+    // Inserts the detached root `new` immediately before `sibling`.
+    pub fn insert_before(&mut self, sibling: NodeId, new: NodeId) -> Result<(), ArenaError> {
+        let node = self.node(sibling)?;
+        let (parent, prev) = (node.parent.ok_or(ArenaError::IsRoot(sibling))?, node.prev_sibling);
+        self.check_attachable(parent, new)?;
+        self.link(new, parent, prev, Some(sibling));
+        Ok(())
+    }
+
+    // Inserts the detached root `new` immediately after `sibling`.
+    pub fn insert_after(&mut self, sibling: NodeId, new: NodeId) -> Result<(), ArenaError> {
+        let node = self.node(sibling)?;
+        let (parent, next) = (node.parent.ok_or(ArenaError::IsRoot(sibling))?, node.next_sibling);
+        self.check_attachable(parent, new)?;
+        self.link(new, parent, Some(sibling), next);
+        Ok(())
+    }
+
+    // Unlinks the subtree rooted at `id` from its parent and siblings. The subtree
+    // itself stays intact and can be attached elsewhere. Detaching a root is a no-op.
+    pub fn detach(&mut self, id: NodeId) -> Result<(), ArenaError> {
+        let node = self.node_mut(id)?;
+        let parent = node.parent.take();
+        let prev = node.prev_sibling.take();
+        let next = node.next_sibling.take();
+        match (prev, parent) {
+            (Some(prev), _) => self.linked_mut(prev).next_sibling = next,
+            (None, Some(parent)) => self.linked_mut(parent).first_child = next,
+            (None, None) => {}
+        }
+        match (next, parent) {
+            (Some(next), _) => self.linked_mut(next).prev_sibling = prev,
+            (None, Some(parent)) => self.linked_mut(parent).last_child = prev,
+            (None, None) => {}
+        }
+        Ok(())
+    }
+
+    // Detaches the subtree rooted at `id` and frees all of its nodes, returning
+    // their values in preorder. Ids of the removed nodes become invalid.
+    pub fn remove_subtree(&mut self, id: NodeId) -> Result<Vec<T>, ArenaError> {
+        self.detach(id)?;
+        let ids: Vec<NodeId> = self.descendants(id).collect();
+        let mut values = Vec::with_capacity(ids.len());
+        for removed in ids {
+            let slot = &mut self.slots[removed.index];
+            let node = slot.node.take().expect("descendants are live");
+            // Wrapping the generation back to 0 would revive ids from the slot's
+            // first use, so a slot whose generations are used up is retired instead.
+            if slot.generation < u32::MAX {
+                slot.generation += 1;
+                self.free.push(removed.index);
+            }
+            values.push(node.value);
+        }
+        self.len -= values.len();
+        Ok(values)
+    }
+
+    fn check_attachable(&self, parent: NodeId, child: NodeId) -> Result<(), ArenaError> {
+        self.node(parent)?;
+        let node = self.node(child)?;
+        if node.parent.is_some() {
+            return Err(ArenaError::NotDetached(child));
+        }
+        // `child` is a root, so a cycle appears exactly when it is `parent` or one of its
+        // ancestors. A childless node can only be its own ancestor, which skips the walk.
+        let has_children = node.first_child.is_some();
+        if parent == child || (has_children && self.ancestors(parent).any(|ancestor| ancestor == child)) {
+            return Err(ArenaError::WouldCreateCycle { parent, child });
+        }
+        Ok(())
+    }
+
+    // Splices a detached `child` into `parent`'s child list between `prev` and `next`.
+    fn link(&mut self, child: NodeId, parent: NodeId, prev: Option<NodeId>, next: Option<NodeId>) {
+        let node = self.linked_mut(child);
+        node.parent = Some(parent);
+        node.prev_sibling = prev;
+        node.next_sibling = next;
+        match prev {
+            Some(prev) => self.linked_mut(prev).next_sibling = Some(child),
+            None => self.linked_mut(parent).first_child = Some(child),
+        }
+        match next {
+            Some(next) => self.linked_mut(next).prev_sibling = Some(child),
+            None => self.linked_mut(parent).last_child = Some(child),
+        }
+    }
+}
+
+impl<T> Default for ArenaTree<T> {
+    fn default() -> Self {
+        ArenaTree::new()
+    }
+}
+
+impl<T> Index<NodeId> for ArenaTree<T> {
+    type Output = T;
+
+    fn index(&self, id: NodeId) -> &T {
+        match self.node(id) {
+            Ok(node) => &node.value,
+            Err(err) => panic!("{}", err),
+        }
+    }
+}
+
+impl<T> IndexMut<NodeId> for ArenaTree<T> {
+    fn index_mut(&mut self, id: NodeId) -> &mut T {
+        match self.node_mut(id) {
+            Ok(node) => &mut node.value,
+            Err(err) => panic!("{}", err),
+        }
+    }
+}
+
+pub struct Children<'a, T> {
+    tree: &'a ArenaTree<T>,
+    front: Option<NodeId>,
+    back: Option<NodeId>,
+}
+
+impl<T> Iterator for Children<'_, T> {
+    type Item = NodeId;
+
+    fn next(&mut self) -> Option<NodeId> {
+        let id = self.front?;
+        if self.front == self.back {
+            self.front = None;
+            self.back = None;
+        } else {
+            self.front = self.tree.next_sibling(id);
+        }
+        Some(id)
+    }
+}
+
+impl<T> DoubleEndedIterator for Children<'_, T> {
+    fn next_back(&mut self) -> Option<NodeId> {
+        let id = self.back?;
+        if self.front == self.back {
+            self.front = None;
+            self.back = None;
+        } else {
+            self.back = self.tree.prev_sibling(id);
+        }
+        Some(id)
+    }
+}
+
+pub struct Ancestors<'a, T> {
+    tree: &'a ArenaTree<T>,
+    next: Option<NodeId>,
+}
+
+impl<T> Iterator for Ancestors<'_, T> {
+    type Item = NodeId;
+
+    fn next(&mut self) -> Option<NodeId> {
+        let id = self.next?;
+        self.next = self.tree.parent(id);
+        Some(id)
+    }
+}
+
+pub struct Descendants<'a, T> {
+    tree: &'a ArenaTree<T>,
+    root: NodeId,
+    next: Option<NodeId>,
+}
+
+impl<T> Iterator for Descendants<'_, T> {
+    type Item = NodeId;
+
+    fn next(&mut self) -> Option<NodeId> {
+        let id = self.next?;
+        // Go down if possible, otherwise to the next sibling of the nearest
+        // ancestor that has one, without leaving the subtree.
+        self.next = self.tree.first_child(id).or_else(|| {
+            let mut node = id;
+            loop {
+                if node == self.root {
+                    return None;
+                }
+                if let Some(sibling) = self.tree.next_sibling(node) {
+                    return Some(sibling);
+                }
+                node = self.tree.parent(node)?;
+            }
+        });
+        Some(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values<'a>(tree: &'a ArenaTree<&str>, ids: impl Iterator<Item = NodeId>) -> Vec<&'a str> {
+        ids.map(|id| tree[id]).collect()
+    }
+
+    // root
+    // ├── a
+    // │   ├── a1
+    // │   └── a2
+    // ├── b
+    // └── c
+    //     └── c1
+    fn sample() -> (ArenaTree<&'static str>, [NodeId; 7]) {
+        let mut tree = ArenaTree::new();
+        let ids = ["root", "a", "a1", "a2", "b", "c", "c1"].map(|name| tree.new_node(name));
+        let [root, a, a1, a2, b, c, c1] = ids;
+        for (parent, child) in [(root, a), (a, a1), (a, a2), (root, b), (root, c), (c, c1)] {
+            tree.append_child(parent, child).unwrap();
+        }
+        (tree, ids)
+    }
+
+    #[test]
+    fn test_navigation() {
+        let (tree, [root, a, a1, a2, b, c, c1]) = sample();
+        assert_eq!(tree.len(), 7);
+        assert_eq!(values(&tree, tree.children(root)), vec!["a", "b", "c"]);
+        assert_eq!(values(&tree, tree.children(root).rev()), vec!["c", "b", "a"]);
+        assert_eq!(tree.parent(a2), Some(a));
+        assert_eq!(tree.parent(root), None);
+        assert_eq!(tree.next_sibling(a), Some(b));
+        assert_eq!(tree.prev_sibling(c), Some(b));
+        assert_eq!(tree.prev_sibling(a), None);
+        assert_eq!(tree.first_child(a), Some(a1));
+        assert_eq!(tree.last_child(root), Some(c));
+        assert_eq!(values(&tree, tree.ancestors(c1)), vec!["c", "root"]);
+        assert_eq!(tree.depth(a2), 2);
+        assert!(tree.is_root(root) && !tree.is_root(b));
+        assert_eq!(
+            values(&tree, tree.descendants(root)),
+            vec!["root", "a", "a1", "a2", "b", "c", "c1"]
+        );
+        // A subtree's walk stops at its own boundary.
+        assert_eq!(values(&tree, tree.descendants(a)), vec!["a", "a1", "a2"]);
+        assert_eq!(values(&tree, tree.descendants(b)), vec!["b"]);
+    }
+
+    #[test]
+    fn test_sibling_insertion() {
+        let (mut tree, [root, a, _, _, b, c, _]) = sample();
+        let first = tree.new_node("first");
+        let mid = tree.new_node("mid");
+        let last = tree.new_node("last");
+        tree.prepend_child(root, first).unwrap();
+        tree.insert_after(a, mid).unwrap();
+        tree.insert_before(c, last).unwrap();
+        assert_eq!(values(&tree, tree.children(root)), vec!["first", "a", "mid", "b", "last", "c"]);
+        assert_eq!(tree.prev_sibling(b), Some(mid));
+
+        let orphan = tree.new_node("orphan");
+        assert_eq!(tree.insert_after(root, orphan), Err(ArenaError::IsRoot(root)));
+    }
+
+    #[test]
+    fn test_detach_and_reattach() {
+        let (mut tree, [root, a, a1, _, b, c, c1]) = sample();
+        tree.detach(a).unwrap();
+        assert!(tree.is_root(a));
+        assert_eq!(values(&tree, tree.children(root)), vec!["b", "c"]);
+        assert_eq!(tree.prev_sibling(b), None);
+        // The detached subtree is intact.
+        assert_eq!(values(&tree, tree.descendants(a)), vec!["a", "a1", "a2"]);
+
+        tree.append_child(c1, a).unwrap();
+        assert_eq!(values(&tree, tree.ancestors(a1)), vec!["a", "c1", "c", "root"]);
+        assert_eq!(
+            values(&tree, tree.descendants(root)),
+            vec!["root", "b", "c", "c1", "a", "a1", "a2"]
+        );
+
+        // Detaching the last child and a root.
+        tree.detach(c).unwrap();
+        assert_eq!(tree.last_child(root), Some(b));
+        tree.detach(root).unwrap();
+        assert_eq!(tree.len(), 7);
+    }
+
+    #[test]
+    fn test_attach_errors() {
+        let (mut tree, [root, a, a1, _, b, _, _]) = sample();
+        assert_eq!(tree.append_child(b, a), Err(ArenaError::NotDetached(a)));
+        assert_eq!(tree.append_child(a1, root), Err(ArenaError::WouldCreateCycle { parent: a1, child: root }));
+        assert_eq!(tree.append_child(root, root), Err(ArenaError::WouldCreateCycle { parent: root, child: root }));
+        // Nothing changed.
+        assert_eq!(values(&tree, tree.children(root)), vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_remove_subtree_invalidates_ids() {
+        let (mut tree, [root, a, a1, a2, b, _, _]) = sample();
+        assert_eq!(tree.remove_subtree(a), Ok(vec!["a", "a1", "a2"]));
+        assert_eq!(tree.len(), 4);
+        assert!(!tree.contains(a1));
+        assert_eq!(tree.get(a2), None);
+        assert_eq!(tree.parent(a), None);
+        assert_eq!(tree.detach(a), Err(ArenaError::InvalidId(a)));
+        assert_eq!(tree.first_child(root), Some(b));
+
+        // Freed slots are reused, but the stale ids still do not resolve.
+        let reused = tree.new_node("new");
+        assert_eq!(reused.index(), a2.index());
+        assert_ne!(reused, a2);
+        assert_eq!(tree[reused], "new");
+        assert!(!tree.contains(a2));
+        assert_eq!(tree.append_child(root, a1), Err(ArenaError::InvalidId(a1)));
+    }
+
+    #[test]
+    fn test_exhausted_generation_retires_slot() {
+        let mut tree = ArenaTree::new();
+        let first = tree.new_node("first");
+        // Pretend the slot has already been reused u32::MAX times.
+        tree.slots[first.index].generation = u32::MAX;
+        let last = NodeId {
+            index: first.index,
+            generation: u32::MAX,
+        };
+        assert_eq!(tree.remove_subtree(last), Ok(vec!["first"]));
+        assert!(tree.is_empty());
+
+        let next = tree.new_node("next");
+        assert_ne!(next.index(), first.index());
+        assert!(!tree.contains(first));
+        assert!(!tree.contains(last));
+        assert_eq!(tree.get(last), None);
+    }
+
+    #[test]
+    fn test_index_mut_and_display() {
+        let (mut tree, [root, ..]) = sample();
+        tree[root] = "top";
+        *tree.get_mut(root).unwrap() = "TOP";
+        assert_eq!(tree[root], "TOP");
+        let err = ArenaError::WouldCreateCycle { parent: root, child: root };
+        assert_eq!(err.to_string(), "attaching #0v0 under #0v0 would create a cycle");
+    }
+
+    #[test]
+    #[should_panic(expected = "does not exist")]
+    fn test_index_with_stale_id_panics() {
+        let (mut tree, [_, a, ..]) = sample();
+        tree.remove_subtree(a).unwrap();
+        let _ = tree[a];
+    }
+
+    #[test]
+    fn test_deep_and_wide_trees() {
+        let n = 200_000;
+        let mut tree = ArenaTree::with_capacity(n);
+        let root = tree.new_node(0usize);
+        let mut tip = root;
+        for value in 1..n {
+            let node = tree.new_node(value);
+            tree.append_child(tip, node).unwrap();
+            tip = node;
+        }
+        assert_eq!(tree.depth(tip), n - 1);
+        assert_eq!(tree.descendants(root).count(), n);
+
+        let star = tree.new_node(0);
+        for value in 0..1000 {
+            let leaf = tree.new_node(value);
+            tree.append_child(star, leaf).unwrap();
+        }
+        assert_eq!(tree.children(star).count(), 1000);
+        assert_eq!(tree.children(star).rev().map(|id| tree[id]).next(), Some(999));
+
+        assert_eq!(tree.remove_subtree(root).unwrap().len(), n);
+        assert_eq!(tree.len(), 1001);
+        // Dropping never recurses: the nodes are plain Vec elements.
+        drop(tree);
+    }
+}
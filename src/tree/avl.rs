@@ -0,0 +1,260 @@
+// 测试特性：AVL 自平衡树、左旋/右旋、按所有权传递子树的递归
+// 语法要点：fn(Box<Node>) -> Box<Node> 所有权进出、Option::take、i32 平衡因子
+// 功能：每个节点记录高度，插入和删除后沿路径重新平衡，使左右子树高度差不超过 1，
+//       保证查找、插入、删除都是 O(log n)
+
+use std::cmp::Ordering;
+use std::mem;
+
+use super::map::{check_in_order, ordered_map_common};
+
+type Link<K, V> = Option<Box<AvlNode<K, V>>>;
+
+#[derive(Debug, Clone)]
+struct AvlNode<K, V> {
+    key: K,
+    value: V,
+    // Height of the subtree rooted here; a leaf has height 1.
+    height: usize,
+    left: Link<K, V>,
+    right: Link<K, V>,
+}
+
+impl<K, V> AvlNode<K, V> {
+    fn new(key: K, value: V) -> Self {
+        AvlNode {
+            key,
+            value,
+            height: 1,
+            left: None,
+            right: None,
+        }
+    }
+
+    fn update_height(&mut self) {
+        self.height = 1 + height(&self.left).max(height(&self.right));
+    }
+
+    // Positive when the left subtree is taller.
+    fn balance_factor(&self) -> i32 {
+        height(&self.left) as i32 - height(&self.right) as i32
+    }
+}
+
+fn height<K, V>(link: &Link<K, V>) -> usize {
+    link.as_ref().map_or(0, |node| node.height)
+}
+
+// An AVL tree: the heights of every node's two subtrees differ by at most one,
+// so the tree height stays below 1.44 log2(n + 2).
+#[derive(Clone)]
+pub struct AvlTree<K, V> {
+    root: Link<K, V>,
+    len: usize,
+}
+
+impl<K, V> AvlTree<K, V> {
+    pub fn new() -> Self {
+        AvlTree { root: None, len: 0 }
+    }
+
+    pub fn height(&self) -> usize {
+        height(&self.root)
+    }
+}
+
+impl<K: Ord, V> AvlTree<K, V> {
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let mut old = None;
+        self.root = Some(insert(self.root.take(), key, value, &mut old));
+        if old.is_none() {
+            self.len += 1;
+        }
+        old
+    }
+
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let mut removed = None;
+        self.root = remove(self.root.take(), key, &mut removed);
+        if removed.is_some() {
+            self.len -= 1;
+        }
+        removed.map(|(_, value)| value)
+    }
+
+    // Checks key order, stored heights and the AVL balance condition at every node.
+    pub fn check_invariants(&self) -> Result<(), String> {
+        check_in_order(self.iter(), self.len)?;
+        check_node(&self.root).map(|_| ())
+    }
+}
+
+ordered_map_common!(AvlTree, AvlNode);
+
+// Returns the verified height of the subtree.
+fn check_node<K, V>(link: &Link<K, V>) -> Result<usize, String> {
+    let Some(node) = link else { return Ok(0) };
+    let left = check_node(&node.left)?;
+    let right = check_node(&node.right)?;
+    if node.height != 1 + left.max(right) {
+        return Err(format!("stored height {} but subtree height is {}", node.height, 1 + left.max(right)));
+    }
+    if left.abs_diff(right) > 1 {
+        return Err(format!("subtree heights {} and {} differ by more than one", left, right));
+    }
+    Ok(node.height)
+}
+
+//     y            x
+//    / \          / \
+//   x   c  ==>   a   y
+//  / \              / \
+// a   b            b   c
+fn rotate_right<K, V>(mut y: Box<AvlNode<K, V>>) -> Box<AvlNode<K, V>> {
+    let mut x = y.left.take().expect("rotate_right needs a left child");
+    y.left = x.right.take();
+    y.update_height();
+    x.right = Some(y);
+    x.update_height();
+    x
+}
+
+fn rotate_left<K, V>(mut x: Box<AvlNode<K, V>>) -> Box<AvlNode<K, V>> {
+    let mut y = x.right.take().expect("rotate_left needs a right child");
+    x.right = y.left.take();
+    x.update_height();
+    y.left = Some(x);
+    y.update_height();
+    y
+}
+
+// Restores the balance condition at `node`, assuming both subtrees are valid AVL trees
+// whose heights differ by at most two. The double-rotation cases (left-right and
+// right-left) first rotate the child so a single rotation suffices.
+fn rebalance<K, V>(mut node: Box<AvlNode<K, V>>) -> Box<AvlNode<K, V>> {
+    node.update_height();
+    let factor = node.balance_factor();
+    if factor > 1 {
+        if node.left.as_ref().is_some_and(|left| left.balance_factor() < 0) {
+            node.left = node.left.take().map(rotate_left);
+        }
+        return rotate_right(node);
+    }
+    if factor < -1 {
+        if node.right.as_ref().is_some_and(|right| right.balance_factor() > 0) {
+            node.right = node.right.take().map(rotate_right);
+        }
+        return rotate_left(node);
+    }
+    node
+}
+
+fn insert<K: Ord, V>(link: Link<K, V>, key: K, value: V, old: &mut Option<V>) -> Box<AvlNode<K, V>> {
+    let Some(mut node) = link else {
+        return Box::new(AvlNode::new(key, value));
+    };
+    match key.cmp(&node.key) {
+        Ordering::Less => node.left = Some(insert(node.left.take(), key, value, old)),
+        Ordering::Greater => node.right = Some(insert(node.right.take(), key, value, old)),
+        Ordering::Equal => {
+            *old = Some(mem::replace(&mut node.value, value));
+            return node;
+        }
+    }
+    rebalance(node)
+}
+
+fn remove<K: Ord, V>(link: Link<K, V>, key: &K, removed: &mut Option<(K, V)>) -> Link<K, V> {
+    let mut node = link?;
+    match key.cmp(&node.key) {
+        Ordering::Less => node.left = remove(node.left.take(), key, removed),
+        Ordering::Greater => node.right = remove(node.right.take(), key, removed),
+        Ordering::Equal => {
+            let AvlNode { key, value, left, right, .. } = *node;
+            *removed = Some((key, value));
+            return match (left, right) {
+                (None, child) | (child, None) => child,
+                // Replace the node by its in-order successor.
+                (Some(left), Some(right)) => {
+                    let (rest, mut successor) = remove_min(right);
+                    successor.left = Some(left);
+                    successor.right = rest;
+                    Some(rebalance(successor))
+                }
+            };
+        }
+    }
+    Some(rebalance(node))
+}
+
+// Detaches the smallest node of a subtree, rebalancing on the way back up.
+fn remove_min<K, V>(mut node: Box<AvlNode<K, V>>) -> (Link<K, V>, Box<AvlNode<K, V>>) {
+    match node.left.take() {
+        None => (node.right.take(), node),
+        Some(left) => {
+            let (rest, min) = remove_min(left);
+            node.left = rest;
+            (Some(rebalance(node)), min)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotations_on_sorted_inserts() {
+        // Ascending inserts trigger left rotations, descending ones right rotations.
+        let ascending: AvlTree<u32, ()> = (0..7).map(|k| (k, ())).collect();
+        assert_eq!(ascending.height(), 3);
+        assert_eq!(ascending.root.as_ref().unwrap().key, 3);
+        let descending: AvlTree<u32, ()> = (0..7).rev().map(|k| (k, ())).collect();
+        assert_eq!(descending.height(), 3);
+        assert_eq!(ascending.check_invariants(), Ok(()));
+        assert_eq!(descending.check_invariants(), Ok(()));
+    }
+
+    #[test]
+    fn test_double_rotations() {
+        // left-right case: 30, 10, 20 and right-left case: 10, 30, 20 both end with 20 at the root
+        for keys in [[30, 10, 20], [10, 30, 20]] {
+            let tree: AvlTree<i32, ()> = keys.iter().map(|&k| (k, ())).collect();
+            assert_eq!(tree.root.as_ref().unwrap().key, 20);
+            assert_eq!(tree.height(), 2);
+        }
+    }
+
+    #[test]
+    fn test_remove_keeps_balance() {
+        let mut tree: AvlTree<u32, u32> = (0..1000).map(|k| (k, k * 2)).collect();
+        for k in (0..1000).step_by(3) {
+            assert_eq!(tree.remove(&k), Some(k * 2));
+            assert_eq!(tree.check_invariants(), Ok(()));
+        }
+        assert_eq!(tree.remove(&0), None);
+        assert_eq!(tree.len(), 666);
+        assert_eq!(tree.min(), Some((&1, &2)));
+        *tree.get_mut(&998).unwrap() = 0;
+        assert_eq!(tree.get(&998), Some(&0));
+    }
+
+    #[test]
+    fn test_corrupted_tree_is_detected() {
+        let mut tree: AvlTree<u32, ()> = (0..3).map(|k| (k, ())).collect();
+        tree.root.as_mut().unwrap().height = 5;
+        assert!(tree.check_invariants().is_err());
+
+        // Hang a chain of two nodes under a leaf: heights 0 and 2 break the balance condition.
+        let mut tree: AvlTree<u32, ()> = [(10, ()), (5, ())].into_iter().collect();
+        let mut child = Box::new(AvlNode::new(20, ()));
+        child.right = Some(Box::new(AvlNode::new(30, ())));
+        child.update_height();
+        let root = tree.root.as_mut().unwrap();
+        root.right = Some(child);
+        root.left = None;
+        root.update_height();
+        tree.len = 3;
+        assert!(tree.check_invariants().unwrap_err().contains("differ"));
+    }
+}
@@ -0,0 +1,346 @@
+// 测试特性：二叉搜索树、有序映射、借用迭代器与所有权迭代器、迭代式的插入和删除
+// 语法要点：&mut Option<Box<Node>> 游标、Option::take、impl Iterator / IntoIterator / FromIterator、自定义 Drop
+// 功能：实现泛型 BinarySearchTree<K, V>：插入、删除(有两个子节点时用后继替换)、查找、
+//       最小/最大键、长度，以及按键升序的借用迭代和所有权迭代
+
+use std::cmp::Ordering;
+use std::mem;
+
+use super::map::{check_in_order, ordered_map_common};
+use super::view;
+
+type Link<K, V> = Option<Box<BstNode<K, V>>>;
+
+#[derive(Debug)]
+struct BstNode<K, V> {
+    key: K,
+    value: V,
+    left: Link<K, V>,
+    right: Link<K, V>,
+}
+
+impl<K, V> BstNode<K, V> {
+    fn new(key: K, value: V) -> Self {
+        BstNode {
+            key,
+            value,
+            left: None,
+            right: None,
+        }
+    }
+}
+
+// An unbalanced binary search tree used as an ordered map. Keys are unique;
+// every key in a node's left subtree is smaller and every key in its right subtree is larger.
+pub struct BinarySearchTree<K, V> {
+    root: Link<K, V>,
+    len: usize,
+}
+
+impl<K, V> BinarySearchTree<K, V> {
+    pub fn new() -> Self {
+        BinarySearchTree { root: None, len: 0 }
+    }
+
+    pub fn clear(&mut self) {
+        *self = BinarySearchTree::new();
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.iter().map(|(key, _)| key)
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.iter().map(|(_, value)| value)
+    }
+
+    pub fn height(&self) -> usize {
+        view::height(self.root.as_deref())
+    }
+}
+
+impl<K: Ord, V> BinarySearchTree<K, V> {
+    // Inserts a key-value pair. If the key already exists its value is replaced
+    // and the old value is returned.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let link = find_link(&mut self.root, &key);
+        match link {
+            Some(node) => Some(mem::replace(&mut node.value, value)),
+            None => {
+                *link = Some(Box::new(BstNode::new(key, value)));
+                self.len += 1;
+                None
+            }
+        }
+    }
+
+    // Removes a key and returns its value. A node with two children is replaced by
+    // its in-order successor (the smallest key of its right subtree).
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        self.remove_entry(key).map(|(_, value)| value)
+    }
+
+    pub fn remove_entry(&mut self, key: &K) -> Option<(K, V)> {
+        let link = find_link(&mut self.root, key);
+        let node = link.take()?;
+        self.len -= 1;
+
+        let BstNode { key, value, left, right } = *node;
+        *link = match (left, right) {
+            (None, child) | (child, None) => child,
+            (Some(left), Some(right)) => {
+                let mut right = Some(right);
+                let min_link = leftmost_link(&mut right);
+                let mut successor = min_link.take().expect("right subtree is not empty");
+                *min_link = successor.right.take();
+                successor.left = Some(left);
+                successor.right = right;
+                Some(successor)
+            }
+        };
+        Some((key, value))
+    }
+
+    // The only structural rule of a plain BST is the key order.
+    pub fn check_invariants(&self) -> Result<(), String> {
+        check_in_order(self.iter(), self.len)
+    }
+}
+
+ordered_map_common!(BinarySearchTree, BstNode);
+
+// Walks down from `link` and returns the link that holds `key`, or the empty link
+// where `key` would be inserted. The comparison is finished before the link is
+// re-borrowed, which keeps the borrow checker happy without recursion.
+fn find_link<'a, K: Ord, V>(mut link: &'a mut Link<K, V>, key: &K) -> &'a mut Link<K, V> {
+    loop {
+        let order = match link.as_deref() {
+            None => return link,
+            Some(node) => key.cmp(&node.key),
+        };
+        if order == Ordering::Equal {
+            return link;
+        }
+        let node = link.as_mut().unwrap();
+        link = if order == Ordering::Less { &mut node.left } else { &mut node.right };
+    }
+}
+
+// Returns the link holding the leftmost node of a non-empty subtree.
+fn leftmost_link<K, V>(mut link: &mut Link<K, V>) -> &mut Link<K, V> {
+    while link.as_ref().is_some_and(|node| node.left.is_some()) {
+        link = &mut link.as_mut().unwrap().left;
+    }
+    link
+}
+
+// Dropping the default way recurses once per level, which overflows the stack on
+// degenerate (list-shaped) trees. Detach children onto an explicit stack instead.
+impl<K, V> Drop for BinarySearchTree<K, V> {
+    fn drop(&mut self) {
+        let mut stack: Vec<Box<BstNode<K, V>>> = self.root.take().into_iter().collect();
+        while let Some(mut node) = stack.pop() {
+            stack.extend(node.left.take());
+            stack.extend(node.right.take());
+        }
+    }
+}
+
+// Copies the tree with the same shape. Like Drop this avoids recursion: nodes are
+// visited in post-order and each copy is assembled from its already copied children.
+impl<K: Clone, V: Clone> Clone for BinarySearchTree<K, V> {
+    fn clone(&self) -> Self {
+        let mut pending: Vec<(&BstNode<K, V>, bool)> = self.root.as_deref().map(|n| (n, false)).into_iter().collect();
+        let mut built: Vec<Box<BstNode<K, V>>> = Vec::new();
+        while let Some((node, children_done)) = pending.pop() {
+            if children_done {
+                // The right child was finished last, so it is on top of the stack.
+                let right = node.right.as_ref().and_then(|_| built.pop());
+                let left = node.left.as_ref().and_then(|_| built.pop());
+                built.push(Box::new(BstNode {
+                    key: node.key.clone(),
+                    value: node.value.clone(),
+                    left,
+                    right,
+                }));
+            } else {
+                pending.push((node, true));
+                pending.extend(node.right.as_deref().map(|n| (n, false)));
+                pending.extend(node.left.as_deref().map(|n| (n, false)));
+            }
+        }
+        BinarySearchTree {
+            root: built.pop(),
+            len: self.len,
+        }
+    }
+}
+
+// Owning iterator: takes nodes apart as it goes, so no clones are needed.
+pub struct IntoIter<K, V> {
+    stack: Vec<Box<BstNode<K, V>>>,
+    remaining: usize,
+}
+
+impl<K, V> IntoIter<K, V> {
+    fn push_left_spine(&mut self, mut link: Link<K, V>) {
+        while let Some(mut node) = link {
+            link = node.left.take();
+            self.stack.push(node);
+        }
+    }
+}
+
+impl<K, V> Iterator for IntoIter<K, V> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<(K, V)> {
+        let mut node = self.stack.pop()?;
+        self.push_left_spine(node.right.take());
+        self.remaining -= 1;
+        let BstNode { key, value, .. } = *node;
+        Some((key, value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<K, V> ExactSizeIterator for IntoIter<K, V> {}
+
+// Nodes left on the stack still own their right subtrees; draining takes them apart
+// one at a time for the same reason the tree itself has a custom Drop.
+impl<K, V> Drop for IntoIter<K, V> {
+    fn drop(&mut self) {
+        for _ in self.by_ref() {}
+    }
+}
+
+impl<K, V> IntoIterator for BinarySearchTree<K, V> {
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V>;
+
+    fn into_iter(mut self) -> IntoIter<K, V> {
+        let mut iter = IntoIter {
+            stack: Vec::new(),
+            remaining: self.len,
+        };
+        iter.push_left_spine(self.root.take());
+        iter
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sort::rng::Rng;
+    use std::collections::BTreeMap;
+
+    fn sample() -> BinarySearchTree<i32, &'static str> {
+        //        50
+        //      /    \
+        //    30      70
+        //   /  \    /  \
+        //  20  40  60  80
+        [(50, "a"), (30, "b"), (70, "c"), (20, "d"), (40, "e"), (60, "f"), (80, "g")]
+            .into_iter()
+            .collect()
+    }
+
+    #[test]
+    fn test_insert_and_get() {
+        let mut tree = sample();
+        assert_eq!(tree.len(), 7);
+        assert_eq!(tree.get(&40), Some(&"e"));
+        assert_eq!(tree.get(&45), None);
+        assert!(tree.contains_key(&80));
+        assert_eq!(tree.insert(40, "E"), Some("e"));
+        assert_eq!(tree.len(), 7);
+        *tree.get_mut(&20).unwrap() = "D";
+        assert_eq!(tree.get(&20), Some(&"D"));
+        assert!(tree.get_mut(&21).is_none());
+    }
+
+    #[test]
+    fn test_min_max_and_iter() {
+        let tree = sample();
+        assert_eq!(tree.min(), Some((&20, &"d")));
+        assert_eq!(tree.max(), Some((&80, &"g")));
+        assert_eq!(tree.keys().copied().collect::<Vec<_>>(), vec![20, 30, 40, 50, 60, 70, 80]);
+        assert_eq!(tree.iter().len(), 7);
+        let owned: Vec<_> = tree.into_iter().collect();
+        assert_eq!(owned[0], (20, "d"));
+        assert_eq!(owned.len(), 7);
+
+        let empty = BinarySearchTree::<i32, i32>::new();
+        assert!(empty.is_empty());
+        assert_eq!(empty.min(), None);
+        assert_eq!(empty.iter().next(), None);
+    }
+
+    #[test]
+    fn test_remove_cases() {
+        let mut tree = sample();
+        // leaf
+        assert_eq!(tree.remove(&20), Some("d"));
+        // one child (30 now only has 40)
+        assert_eq!(tree.remove(&30), Some("b"));
+        // two children: the root is replaced by its successor 60
+        assert_eq!(tree.remove(&50), Some("a"));
+        assert_eq!(tree.root.as_ref().unwrap().key, 60);
+        assert_eq!(tree.remove(&50), None);
+        assert_eq!(tree.keys().copied().collect::<Vec<_>>(), vec![40, 60, 70, 80]);
+        assert_eq!(tree.len(), 4);
+        assert_eq!(tree.remove_entry(&70), Some((70, "c")));
+    }
+
+    #[test]
+    fn test_matches_btreemap() {
+        let mut tree = BinarySearchTree::new();
+        let mut model = BTreeMap::new();
+        let mut rng = Rng::new(0x2545_f491);
+        for step in 0..5_000 {
+            let key = rng.below(500);
+            if step % 3 == 0 {
+                assert_eq!(tree.remove(&key), model.remove(&key));
+            } else {
+                assert_eq!(tree.insert(key, step), model.insert(key, step));
+            }
+            assert_eq!(tree.len(), model.len());
+        }
+        assert!(tree.iter().eq(model.iter()));
+        assert_eq!(tree.min(), model.iter().next());
+        assert_eq!(tree.max(), model.iter().next_back());
+    }
+
+    #[test]
+    fn test_degenerate_tree_does_not_overflow() {
+        // The shape sorted insertion produces: a list 100k levels deep. It is built
+        // directly because inserting that many sorted keys one by one is quadratic.
+        let mut tree = BinarySearchTree::new();
+        for key in (0..100_000u32).rev() {
+            let mut node = Box::new(BstNode::new(key, ()));
+            node.right = tree.root.take();
+            tree.root = Some(node);
+            tree.len += 1;
+        }
+        assert_eq!(tree.insert(100_000, ()), None);
+        assert_eq!(tree.remove(&100_000), Some(()));
+        assert_eq!(tree.max(), Some((&99_999, &())));
+        assert_eq!(tree.remove(&0), Some(()));
+        assert_eq!(tree.iter().count(), 99_999);
+        let copy = tree.clone();
+        assert!(copy.iter().eq(tree.iter()));
+        let mut owned = copy.into_iter();
+        assert_eq!(owned.next(), Some((1, ())));
+        drop(owned);
+        drop(tree);
+    }
+
+    #[test]
+    fn test_debug_format() {
+        let tree: BinarySearchTree<_, _> = [(2, 'b'), (1, 'a')].into_iter().collect();
+        assert_eq!(format!("{:?}", tree), "{1: 'a', 2: 'b'}");
+    }
+}
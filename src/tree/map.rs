@@ -0,0 +1,310 @@
+// 测试特性：Trait抽象、泛型关联类型(GAT)、不变式检查
+// 语法要点：type Iter<'a>: Iterator where Self: 'a、trait 默认方法、泛型函数约束 M: OrderedMap<K, V>、macro_rules! 生成重复的 impl
+// 功能：定义二叉搜索树、AVL 树和红黑树共用的有序映射接口，
+//       以及供各实现复用的中序有序性检查和公共实现宏 ordered_map_common!
+
+// The ordered-map operations every tree in this module supports.
+pub trait OrderedMap<K: Ord, V> {
+    type Iter<'a>: Iterator<Item = (&'a K, &'a V)>
+    where
+        Self: 'a,
+        K: 'a,
+        V: 'a;
+
+    // Inserts or replaces; returns the previous value for the key.
+    fn insert(&mut self, key: K, value: V) -> Option<V>;
+    fn get(&self, key: &K) -> Option<&V>;
+    fn get_mut(&mut self, key: &K) -> Option<&mut V>;
+    fn remove(&mut self, key: &K) -> Option<V>;
+    fn len(&self) -> usize;
+    fn min(&self) -> Option<(&K, &V)>;
+    fn max(&self) -> Option<(&K, &V)>;
+    // Entries in ascending key order.
+    fn iter(&self) -> Self::Iter<'_>;
+    // Number of nodes on the longest root-to-leaf path (0 for an empty tree).
+    fn height(&self) -> usize;
+    // Verifies the structural invariants of the implementation, describing the first violation.
+    fn check_invariants(&self) -> Result<(), String>;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn contains_key(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+}
+
+// Generates everything the trees have in common, so lookups and traversal are written
+// once (in `view`) instead of once per tree. `$node` must have `key`, `value`, `left`
+// and `right` fields, `$tree` must have `root` and `len` fields, and the tree supplies
+// its own inherent `new`, `insert`, `remove`, `height` and `check_invariants`.
+macro_rules! ordered_map_common {
+    ($tree:ident, $node:ident) => {
+        impl<K, V> $crate::tree::view::BinaryNode for $node<K, V> {
+            fn left(&self) -> Option<&Self> {
+                self.left.as_deref()
+            }
+
+            fn right(&self) -> Option<&Self> {
+                self.right.as_deref()
+            }
+        }
+
+        impl<K, V> $crate::tree::view::EntryNode for $node<K, V> {
+            type Key = K;
+
+            fn key(&self) -> &K {
+                &self.key
+            }
+
+            fn left_mut(&mut self) -> Option<&mut Self> {
+                self.left.as_deref_mut()
+            }
+
+            fn right_mut(&mut self) -> Option<&mut Self> {
+                self.right.as_deref_mut()
+            }
+        }
+
+        impl<K, V> $tree<K, V> {
+            pub fn len(&self) -> usize {
+                self.len
+            }
+
+            pub fn is_empty(&self) -> bool {
+                self.len == 0
+            }
+
+            // The leftmost node holds the smallest key.
+            pub fn min(&self) -> Option<(&K, &V)> {
+                $crate::tree::view::leftmost(self.root.as_deref()).map(|node| (&node.key, &node.value))
+            }
+
+            // The rightmost node holds the largest key.
+            pub fn max(&self) -> Option<(&K, &V)> {
+                $crate::tree::view::rightmost(self.root.as_deref()).map(|node| (&node.key, &node.value))
+            }
+
+            // Borrowing in-order iterator: yields entries in ascending key order.
+            pub fn iter(&self) -> Iter<'_, K, V> {
+                Iter {
+                    nodes: $crate::tree::view::InOrderNodes::new(self.root.as_deref()),
+                    remaining: self.len,
+                }
+            }
+        }
+
+        impl<K: Ord, V> $tree<K, V> {
+            pub fn get(&self, key: &K) -> Option<&V> {
+                $crate::tree::view::find(self.root.as_deref(), key).map(|node| &node.value)
+            }
+
+            pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+                $crate::tree::view::find_mut(self.root.as_deref_mut(), key).map(|node| &mut node.value)
+            }
+
+            pub fn contains_key(&self, key: &K) -> bool {
+                self.get(key).is_some()
+            }
+        }
+
+        impl<K: Ord, V> $crate::tree::map::OrderedMap<K, V> for $tree<K, V> {
+            type Iter<'a>
+                = Iter<'a, K, V>
+            where
+                Self: 'a,
+                K: 'a,
+                V: 'a;
+
+            fn insert(&mut self, key: K, value: V) -> Option<V> {
+                $tree::insert(self, key, value)
+            }
+
+            fn get(&self, key: &K) -> Option<&V> {
+                $tree::get(self, key)
+            }
+
+            fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+                $tree::get_mut(self, key)
+            }
+
+            fn remove(&mut self, key: &K) -> Option<V> {
+                $tree::remove(self, key)
+            }
+
+            fn len(&self) -> usize {
+                self.len
+            }
+
+            fn min(&self) -> Option<(&K, &V)> {
+                $tree::min(self)
+            }
+
+            fn max(&self) -> Option<(&K, &V)> {
+                $tree::max(self)
+            }
+
+            fn iter(&self) -> Iter<'_, K, V> {
+                $tree::iter(self)
+            }
+
+            fn height(&self) -> usize {
+                $tree::height(self)
+            }
+
+            fn check_invariants(&self) -> Result<(), String> {
+                $tree::check_invariants(self)
+            }
+        }
+
+        impl<K, V> Default for $tree<K, V> {
+            fn default() -> Self {
+                $tree::new()
+            }
+        }
+
+        impl<K: ::std::fmt::Debug, V: ::std::fmt::Debug> ::std::fmt::Debug for $tree<K, V> {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                f.debug_map().entries(self.iter()).finish()
+            }
+        }
+
+        impl<K: Ord, V> FromIterator<(K, V)> for $tree<K, V> {
+            fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+                let mut tree = $tree::new();
+                tree.extend(iter);
+                tree
+            }
+        }
+
+        impl<K: Ord, V> Extend<(K, V)> for $tree<K, V> {
+            fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+                for (key, value) in iter {
+                    self.insert(key, value);
+                }
+            }
+        }
+
+        // Borrowing in-order iterator; the node walk itself lives in `view`.
+        pub struct Iter<'a, K, V> {
+            nodes: $crate::tree::view::InOrderNodes<'a, $node<K, V>>,
+            remaining: usize,
+        }
+
+        impl<'a, K, V> Iterator for Iter<'a, K, V> {
+            type Item = (&'a K, &'a V);
+
+            fn next(&mut self) -> Option<Self::Item> {
+                let node = self.nodes.next()?;
+                self.remaining -= 1;
+                Some((&node.key, &node.value))
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                (self.remaining, Some(self.remaining))
+            }
+        }
+
+        impl<K, V> ExactSizeIterator for Iter<'_, K, V> {}
+
+        impl<'a, K, V> IntoIterator for &'a $tree<K, V> {
+            type Item = (&'a K, &'a V);
+            type IntoIter = Iter<'a, K, V>;
+
+            fn into_iter(self) -> Iter<'a, K, V> {
+                self.iter()
+            }
+        }
+    };
+}
+
+pub(crate) use ordered_map_common;
+
+// An in-order walk of a binary search tree must yield strictly increasing keys,
+// and exactly as many of them as the tree claims to hold.
+pub(crate) fn check_in_order<'a, K, V, I>(entries: I, len: usize) -> Result<(), String>
+where
+    K: Ord + 'a,
+    V: 'a,
+    I: Iterator<Item = (&'a K, &'a V)>,
+{
+    let mut count = 0;
+    let mut previous: Option<&K> = None;
+    for (key, _) in entries {
+        if previous.is_some_and(|prev| prev >= key) {
+            return Err(format!("keys out of order at in-order position {}", count));
+        }
+        previous = Some(key);
+        count += 1;
+    }
+    if count != len {
+        return Err(format!("len is {} but the tree holds {} entries", len, count));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sort::rng::Rng;
+    use crate::tree::{AvlTree, BinarySearchTree, RedBlackTree};
+    use std::collections::BTreeMap;
+
+    // Applies a stream of inserts and removes to `map` and to a BTreeMap model,
+    // checking that both agree and the invariants hold throughout.
+    fn run_against_model<M: OrderedMap<u32, u32>>(mut map: M, ops: &[(bool, u32)]) -> M {
+        let mut model = BTreeMap::new();
+        for (step, &(is_insert, key)) in ops.iter().enumerate() {
+            if is_insert {
+                assert_eq!(map.insert(key, step as u32), model.insert(key, step as u32));
+            } else {
+                assert_eq!(map.remove(&key), model.remove(&key));
+            }
+            assert_eq!(map.len(), model.len());
+            if step % 64 == 0 {
+                assert_eq!(map.check_invariants(), Ok(()), "step {}", step);
+            }
+        }
+        assert_eq!(map.check_invariants(), Ok(()));
+        assert!(map.iter().eq(model.iter()));
+        assert_eq!(map.min(), model.iter().next());
+        assert_eq!(map.max(), model.iter().next_back());
+        for key in 0..600 {
+            assert_eq!(map.get(&key), model.get(&key));
+            assert_eq!(map.contains_key(&key), model.contains_key(&key));
+        }
+        map
+    }
+
+    fn random_ops(seed: u64) -> Vec<(bool, u32)> {
+        let mut rng = Rng::new(seed);
+        (0..4000).map(|_| (rng.below(3) != 0, rng.below(600) as u32)).collect()
+    }
+
+    #[test]
+    fn test_all_trees_match_model_on_random_ops() {
+        for seed in 0..4 {
+            let ops = random_ops(seed);
+            run_against_model(BinarySearchTree::new(), &ops);
+            run_against_model(AvlTree::new(), &ops);
+            run_against_model(RedBlackTree::new(), &ops);
+        }
+    }
+
+    #[test]
+    fn test_balanced_trees_stay_logarithmic_on_sorted_inserts() {
+        let n = 4095u32;
+        let mut ops: Vec<(bool, u32)> = (0..n).map(|k| (true, k)).collect();
+        ops.extend((0..n).step_by(3).map(|k| (false, k)));
+
+        let avl = run_against_model(AvlTree::new(), &ops);
+        let red_black = run_against_model(RedBlackTree::new(), &ops);
+        // log2(4096) = 12: AVL height is below 1.45 log2 n, red-black below 2 log2 n
+        assert!(avl.height() <= 17, "avl height {}", avl.height());
+        assert!(red_black.height() <= 24, "red-black height {}", red_black.height());
+
+        let bst = run_against_model(BinarySearchTree::new(), &ops[..500]);
+        assert_eq!(bst.height(), 500);
+    }
+}
@@ -1,62 +1,135 @@
-// 测试特性：二叉树数据结构、泛型、Box智能指针、递归遍历
-// 语法要点：Option<Box<T>>、enum模式匹配、泛型<T>、递归函数
-// 功能：实现泛型二叉树和三种遍历算法(前序、中序、后序)
+// 测试特性：二叉树数据结构、泛型、Box智能指针、迭代器遍历
+// 语法要点：Option<Box<T>>、enum模式匹配、泛型<T>、显式栈/队列实现的迭代器、闭包参数 FnMut
+// 功能：实现泛型二叉树和四种遍历(前序、中序、后序、层序)，遍历以迭代器或闭包形式提供
+
+use std::mem::ManuallyDrop;
+use std::ptr;
+
+pub mod map;
+pub mod bst;
+pub mod avl;
+pub mod red_black;
+pub mod traversal;
+pub mod serialize;
+pub mod algorithms;
+pub mod arena;
+mod view;
+
+pub use map::OrderedMap;
+pub use bst::BinarySearchTree;
+pub use avl::AvlTree;
+pub use red_black::RedBlackTree;
+pub use traversal::{InOrder, IntoInOrder, LevelOrder, PostOrder, PreOrder};
+pub use serialize::BuildError;
+pub use arena::{ArenaError, ArenaTree, NodeId};
+
 // Use a generic type `T` to make the tree work with any data type.
 // `Option<Box<Node<T>>>` is used to handle optional children and
 // to manage heap allocation for the nodes.
 #[derive(Debug)]
-struct Node<T> {
-    value: T,
-    left: Option<Box<Node<T>>>,
-    right: Option<Box<Node<T>>>,
+pub struct Node<T> {
+    pub value: T,
+    pub left: Option<Box<Node<T>>>,
+    pub right: Option<Box<Node<T>>>,
 }
 
 impl<T> Node<T> {
     // A simple constructor to create a new leaf node.
-    fn new(value: T) -> Self {
+    pub fn new(value: T) -> Self {
         Node {
             value,
             left: None,
             right: None,
         }
     }
+
+    pub fn iter_preorder(&self) -> PreOrder<'_, T> {
+        PreOrder::new(Some(self))
+    }
+
+    pub fn iter_inorder(&self) -> InOrder<'_, T> {
+        InOrder::new(Some(self))
+    }
+
+    pub fn iter_postorder(&self) -> PostOrder<'_, T> {
+        PostOrder::new(Some(self))
+    }
+
+    pub fn iter_level_order(&self) -> LevelOrder<'_, T> {
+        LevelOrder::new(Some(self))
+    }
+
+    // Consumes the tree and yields its values in order using O(1) extra space.
+    pub fn into_inorder(self) -> IntoInOrder<T> {
+        IntoInOrder::new(Some(Box::new(self)))
+    }
+
+    // Takes the value out of a node whose children have already been detached.
+    // Moving a field out directly is not allowed because `Node` implements Drop.
+    fn into_value(self) -> T {
+        debug_assert!(self.left.is_none() && self.right.is_none());
+        let node = ManuallyDrop::new(self);
+        // SAFETY: `node` is never used or dropped again and its links are empty,
+        // so the value is moved out exactly once and nothing else is leaked.
+        unsafe { ptr::read(&node.value) }
+    }
+
+    // Calls `f` on every value in the given order.
+    pub fn visit<F: FnMut(&T)>(&self, order: TraversalType, f: F) {
+        visit(Some(self), order, f);
+    }
+}
+
+// The default drop would recurse once per level; take the children apart
+// with an explicit stack so deep trees can be dropped too.
+impl<T> Drop for Node<T> {
+    fn drop(&mut self) {
+        let mut stack: Vec<Box<Node<T>>> = self.left.take().into_iter().chain(self.right.take()).collect();
+        while let Some(mut node) = stack.pop() {
+            stack.extend(node.left.take());
+            stack.extend(node.right.take());
+        }
+    }
+}
+
+// A tree of depth n where every node only has a left child, with values
+// n - 1, ..., 1, 0 from the root down. Shared by the deep-tree tests.
+#[cfg(test)]
+pub(crate) fn left_chain(n: i32) -> Node<i32> {
+    let mut root = Node::new(0);
+    for value in 1..n {
+        let mut parent = Node::new(value);
+        parent.left = Some(Box::new(root));
+        root = parent;
+    }
+    root
 }
 
 // An enum to represent the different traversal types.
-enum TraversalType {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraversalType {
     InOrder,
     PreOrder,
     PostOrder,
+    LevelOrder,
 }
 
-// A function to perform the tree traversal. It takes a reference to the root
-// node and the desired traversal type.
+// Calls `f` on every value of a possibly empty tree in the given order.
+pub fn visit<T, F: FnMut(&T)>(root: Option<&Node<T>>, order: TraversalType, f: F) {
+    match order {
+        TraversalType::PreOrder => PreOrder::new(root).for_each(f),
+        TraversalType::InOrder => InOrder::new(root).for_each(f),
+        TraversalType::PostOrder => PostOrder::new(root).for_each(f),
+        TraversalType::LevelOrder => LevelOrder::new(root).for_each(f),
+    }
+}
+
+// Prints every value of the tree on its own line in the given order.
 pub fn traverse<T: std::fmt::Display>(
     node: &Option<Box<Node<T>>>,
     traversal_type: &TraversalType,
 ) {
-    if let Some(n) = node {
-        match traversal_type {
-            TraversalType::PreOrder => {
-                // Visit the root first, then left, then right.
-                println!("{}", n.value);
-                traverse(&n.left, traversal_type);
-                traverse(&n.right, traversal_type);
-            }
-            TraversalType::InOrder => {
-                // Visit left, then the root, then right.
-                traverse(&n.left, traversal_type);
-                println!("{}", n.value);
-                traverse(&n.right, traversal_type);
-            }
-            TraversalType::PostOrder => {
-                // Visit left, then right, then the root.
-                traverse(&n.left, traversal_type);
-                traverse(&n.right, traversal_type);
-                println!("{}", n.value);
-            }
-        }
-    }
+    visit(node.as_deref(), *traversal_type, |value| println!("{}", value));
 }
 
 // The main function where we create and tranverse the tree.
@@ -67,19 +140,11 @@ pub fn main() {
     //    2   3
     //   / \
     //  4   5
-    let mut root = Node::new(1);
-    let mut node2 = Node::new(2);
-    let node3 = Node::new(3);
-    let node4 = Node::new(4);
-    let node5 = Node::new(5);
-
-    node2.left = Some(Box::new(node4));
-    node2.right = Some(Box::new(node5));
-    root.left = Some(Box::new(node2));
-    root.right = Some(Box::new(node3));
-
-    // Convert the root node into an Option for the `tranverse` function.
-    let tree = Some(Box::new(root));
+    let tree = Node::from_level_order([Some(1), Some(2), Some(3), Some(4), Some(5)])
+        .expect("every value has a parent");
+    if let Some(root) = tree.as_deref() {
+        print!("{}", root.to_ascii());
+    }
 
     // Perform and print the different traversals.
     println!("--- Pre-Order Traversal ---");
@@ -90,4 +155,7 @@ pub fn main() {
 
     println!("\n--- Post-Order Traversal ---");
     traverse(&tree, &TraversalType::PostOrder);
+
+    println!("\n--- Level-Order Traversal ---");
+    traverse(&tree, &TraversalType::LevelOrder);
 }
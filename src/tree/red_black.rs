@@ -0,0 +1,307 @@
+// 测试特性：红黑树(左倾红黑树 LLRB)、颜色翻转、旋转、2-3 树对应关系
+// 语法要点：enum Color、所有权进出的递归(Box<Node> -> Box<Node>)、mem::swap 交换键值
+// 功能：用左倾红黑树实现有序映射：红链接只出现在左侧且不连续，
+//       根到每个空链接的黑链接数相同，因此树高不超过 2 log2(n + 1)
+
+use std::cmp::Ordering;
+use std::mem;
+
+use super::map::{check_in_order, ordered_map_common};
+use super::view;
+
+type Link<K, V> = Option<Box<RbNode<K, V>>>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Color {
+    Red,
+    Black,
+}
+
+impl Color {
+    fn flip(self) -> Color {
+        match self {
+            Color::Red => Color::Black,
+            Color::Black => Color::Red,
+        }
+    }
+}
+
+// The color is that of the link from the parent to this node.
+#[derive(Debug, Clone)]
+struct RbNode<K, V> {
+    key: K,
+    value: V,
+    color: Color,
+    left: Link<K, V>,
+    right: Link<K, V>,
+}
+
+impl<K, V> RbNode<K, V> {
+    // New nodes are always attached with a red link.
+    fn new(key: K, value: V) -> Self {
+        RbNode {
+            key,
+            value,
+            color: Color::Red,
+            left: None,
+            right: None,
+        }
+    }
+}
+
+fn is_red<K, V>(link: &Link<K, V>) -> bool {
+    link.as_ref().is_some_and(|node| node.color == Color::Red)
+}
+
+// Whether the left child of `link` exists and is itself red on its left.
+fn is_red_left_left<K, V>(link: &Link<K, V>) -> bool {
+    link.as_ref().is_some_and(|node| is_red(&node.left))
+}
+
+// A left-leaning red-black tree (Sedgewick), an isometry of 2-3 trees:
+// red links lean left, no node has two red links, and every path from the root
+// to an empty link crosses the same number of black links.
+#[derive(Clone)]
+pub struct RedBlackTree<K, V> {
+    root: Link<K, V>,
+    len: usize,
+}
+
+impl<K, V> RedBlackTree<K, V> {
+    pub fn new() -> Self {
+        RedBlackTree { root: None, len: 0 }
+    }
+
+    pub fn height(&self) -> usize {
+        view::height(self.root.as_deref())
+    }
+}
+
+impl<K: Ord, V> RedBlackTree<K, V> {
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let mut old = None;
+        let mut root = insert(self.root.take(), key, value, &mut old);
+        root.color = Color::Black;
+        self.root = Some(root);
+        if old.is_none() {
+            self.len += 1;
+        }
+        old
+    }
+
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        // The top-down deletion below assumes the key is present.
+        if !self.contains_key(key) {
+            return None;
+        }
+        let mut root = self.root.take()?;
+        if !is_red(&root.left) && !is_red(&root.right) {
+            root.color = Color::Red;
+        }
+        let mut removed = None;
+        self.root = remove(root, key, &mut removed);
+        if let Some(root) = self.root.as_mut() {
+            root.color = Color::Black;
+        }
+        self.len -= 1;
+        removed.map(|(_, value)| value)
+    }
+
+    // Checks key order, that the root is black, that red links lean left and never
+    // come in pairs, and that all paths have the same black height.
+    pub fn check_invariants(&self) -> Result<(), String> {
+        check_in_order(self.iter(), self.len)?;
+        if is_red(&self.root) {
+            return Err("the root is red".to_string());
+        }
+        check_node(&self.root).map(|_| ())
+    }
+}
+
+ordered_map_common!(RedBlackTree, RbNode);
+
+// Returns the black height of the subtree.
+fn check_node<K, V>(link: &Link<K, V>) -> Result<usize, String> {
+    let Some(node) = link else { return Ok(1) };
+    if is_red(&node.right) {
+        return Err("a red link leans right".to_string());
+    }
+    if node.color == Color::Red && is_red(&node.left) {
+        return Err("two red links in a row".to_string());
+    }
+    let left = check_node(&node.left)?;
+    let right = check_node(&node.right)?;
+    if left != right {
+        return Err(format!("black heights {} and {} differ", left, right));
+    }
+    Ok(left + usize::from(node.color == Color::Black))
+}
+
+fn rotate_left<K, V>(mut h: Box<RbNode<K, V>>) -> Box<RbNode<K, V>> {
+    let mut x = h.right.take().expect("rotate_left needs a right child");
+    h.right = x.left.take();
+    x.color = h.color;
+    h.color = Color::Red;
+    x.left = Some(h);
+    x
+}
+
+fn rotate_right<K, V>(mut h: Box<RbNode<K, V>>) -> Box<RbNode<K, V>> {
+    let mut x = h.left.take().expect("rotate_right needs a left child");
+    h.left = x.right.take();
+    x.color = h.color;
+    h.color = Color::Red;
+    x.right = Some(h);
+    x
+}
+
+// Splits (or, during deletion, re-forms) a temporary 4-node.
+fn flip_colors<K, V>(h: &mut RbNode<K, V>) {
+    h.color = h.color.flip();
+    for child in [h.left.as_mut(), h.right.as_mut()].into_iter().flatten() {
+        child.color = child.color.flip();
+    }
+}
+
+// Restores the left-leaning invariants on the way back up.
+fn balance<K, V>(mut h: Box<RbNode<K, V>>) -> Box<RbNode<K, V>> {
+    if is_red(&h.right) && !is_red(&h.left) {
+        h = rotate_left(h);
+    }
+    if is_red(&h.left) && is_red_left_left(&h.left) {
+        h = rotate_right(h);
+    }
+    if is_red(&h.left) && is_red(&h.right) {
+        flip_colors(&mut h);
+    }
+    h
+}
+
+fn insert<K: Ord, V>(link: Link<K, V>, key: K, value: V, old: &mut Option<V>) -> Box<RbNode<K, V>> {
+    let Some(mut h) = link else {
+        return Box::new(RbNode::new(key, value));
+    };
+    match key.cmp(&h.key) {
+        Ordering::Less => h.left = Some(insert(h.left.take(), key, value, old)),
+        Ordering::Greater => h.right = Some(insert(h.right.take(), key, value, old)),
+        Ordering::Equal => *old = Some(mem::replace(&mut h.value, value)),
+    }
+    balance(h)
+}
+
+// Assuming h is red and both h.left and h.left.left are black,
+// make h.left or one of its children red.
+fn move_red_left<K, V>(mut h: Box<RbNode<K, V>>) -> Box<RbNode<K, V>> {
+    flip_colors(&mut h);
+    if h.right.as_ref().is_some_and(|right| is_red(&right.left)) {
+        h.right = h.right.take().map(rotate_right);
+        h = rotate_left(h);
+        flip_colors(&mut h);
+    }
+    h
+}
+
+// Assuming h is red and both h.right and h.right.left are black,
+// make h.right or one of its children red.
+fn move_red_right<K, V>(mut h: Box<RbNode<K, V>>) -> Box<RbNode<K, V>> {
+    flip_colors(&mut h);
+    if is_red_left_left(&h.left) {
+        h = rotate_right(h);
+        flip_colors(&mut h);
+    }
+    h
+}
+
+// Detaches the smallest node, keeping the current node out of a 2-node on the way down.
+fn remove_min<K, V>(mut h: Box<RbNode<K, V>>) -> (Link<K, V>, Box<RbNode<K, V>>) {
+    if h.left.is_none() {
+        return (h.right.take(), h);
+    }
+    if !is_red(&h.left) && !is_red_left_left(&h.left) {
+        h = move_red_left(h);
+    }
+    let (rest, min) = remove_min(h.left.take().expect("left child checked above"));
+    h.left = rest;
+    (Some(balance(h)), min)
+}
+
+// Top-down deletion of a key known to be in the subtree.
+fn remove<K: Ord, V>(mut h: Box<RbNode<K, V>>, key: &K, removed: &mut Option<(K, V)>) -> Link<K, V> {
+    if key < &h.key {
+        if !is_red(&h.left) && !is_red_left_left(&h.left) {
+            h = move_red_left(h);
+        }
+        h.left = remove(h.left.take().expect("key is in the left subtree"), key, removed);
+    } else {
+        if is_red(&h.left) {
+            h = rotate_right(h);
+        }
+        if key == &h.key && h.right.is_none() {
+            let RbNode { key, value, .. } = *h;
+            *removed = Some((key, value));
+            return None;
+        }
+        if !is_red(&h.right) && !is_red_left_left(&h.right) {
+            h = move_red_right(h);
+        }
+        if key == &h.key {
+            // Replace this node's entry with its successor's and delete the successor.
+            let (rest, mut successor) = remove_min(h.right.take().expect("node has a right child"));
+            mem::swap(&mut h.key, &mut successor.key);
+            mem::swap(&mut h.value, &mut successor.value);
+            *removed = Some((successor.key, successor.value));
+            h.right = rest;
+        } else {
+            h.right = remove(h.right.take().expect("key is in the right subtree"), key, removed);
+        }
+    }
+    Some(balance(h))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_keeps_invariants() {
+        let mut tree = RedBlackTree::new();
+        for k in 0..1000u32 {
+            tree.insert(k, k);
+            assert_eq!(tree.check_invariants(), Ok(()), "after inserting {}", k);
+        }
+        assert!(tree.height() <= 2 * 10);
+        assert_eq!(tree.insert(500, 0), Some(500));
+        assert_eq!(tree.len(), 1000);
+    }
+
+    #[test]
+    fn test_remove_keeps_invariants() {
+        let mut tree: RedBlackTree<u32, u32> = (0..500).map(|k| (k, k)).collect();
+        for k in (0..500).rev().step_by(2) {
+            assert_eq!(tree.remove(&k), Some(k));
+            assert_eq!(tree.check_invariants(), Ok(()), "after removing {}", k);
+        }
+        assert_eq!(tree.remove(&1), None);
+        for k in (0..500).step_by(2) {
+            assert_eq!(tree.remove(&k), Some(k));
+        }
+        assert!(tree.is_empty());
+        assert!(tree.root.is_none());
+    }
+
+    #[test]
+    fn test_corrupted_tree_is_detected() {
+        let mut tree: RedBlackTree<u32, ()> = (0..3).map(|k| (k, ())).collect();
+        tree.root.as_mut().unwrap().right.as_mut().unwrap().color = Color::Red;
+        assert_eq!(tree.check_invariants(), Err("a red link leans right".to_string()));
+
+        let mut tree: RedBlackTree<u32, ()> = (0..3).map(|k| (k, ())).collect();
+        tree.root.as_mut().unwrap().color = Color::Red;
+        assert_eq!(tree.check_invariants(), Err("the root is red".to_string()));
+
+        let mut tree: RedBlackTree<u32, ()> = (0..3).map(|k| (k, ())).collect();
+        tree.root.as_mut().unwrap().left.as_mut().unwrap().color = Color::Red;
+        tree.root.as_mut().unwrap().right.as_mut().unwrap().color = Color::Black;
+        assert!(tree.check_invariants().unwrap_err().contains("black heights"));
+    }
+}
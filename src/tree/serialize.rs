@@ -0,0 +1,390 @@
+// 测试特性：树的构建与序列化、自定义错误类型、JSON(serde_json)、Graphviz DOT、ASCII 绘图
+// 语法要点：Result<Option<Box<Node<T>>>, BuildError>、VecDeque 层序构建、按下标自底向上组装、json!
+// 功能：从带 None 空位的层序数组(LeetCode 风格)或前序+中序序列构建二叉树，
+//       并导出为层序数组、嵌套 JSON、DOT 图描述和 ASCII 树形图；全程不递归，深树也能处理
+
+use std::collections::{HashSet, VecDeque};
+use std::error::Error;
+use std::fmt::{self, Display, Write};
+use std::hash::Hash;
+
+use serde_json::{json, Value};
+
+use super::algorithms::fold_postorder;
+use super::Node;
+
+type Tree<T> = Option<Box<Node<T>>>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BuildError {
+    // A level-order value that no earlier node can take as a child.
+    OrphanValue { index: usize },
+    LengthMismatch { preorder: usize, inorder: usize },
+    // Preorder/inorder reconstruction needs distinct values.
+    DuplicateValue { index: usize },
+    // The preorder and inorder sequences do not describe the same tree.
+    Inconsistent,
+}
+
+impl Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BuildError::OrphanValue { index } => write!(f, "value at index {} has no parent", index),
+            BuildError::LengthMismatch { preorder, inorder } => {
+                write!(f, "preorder has {} values but inorder has {}", preorder, inorder)
+            }
+            BuildError::DuplicateValue { index } => write!(f, "duplicate value at preorder index {}", index),
+            BuildError::Inconsistent => write!(f, "preorder and inorder sequences are inconsistent"),
+        }
+    }
+}
+
+impl Error for BuildError {}
+
+// Every child has a larger index than its parent in both level order and preorder,
+// so the tree can be assembled from the last index backwards without recursion.
+fn assemble<T>(values: Vec<Option<T>>, children: &[(Option<usize>, Option<usize>)]) -> Tree<T> {
+    let mut built: Vec<Tree<T>> = (0..values.len()).map(|_| None).collect();
+    for (index, value) in values.into_iter().enumerate().rev() {
+        if let Some(value) = value {
+            let (left, right) = children[index];
+            let mut node = Node::new(value);
+            node.left = left.and_then(|child| built[child].take());
+            node.right = right.and_then(|child| built[child].take());
+            built[index] = Some(Box::new(node));
+        }
+    }
+    built.into_iter().next().flatten()
+}
+
+impl<T> Node<T> {
+    // Builds a tree from a level-order listing in which `None` marks a missing child,
+    // e.g. [1, None, 2, 3] is 1 with right child 2, which has left child 3.
+    // Only present nodes have child slots, and trailing `None`s may be omitted.
+    pub fn from_level_order<I>(values: I) -> Result<Tree<T>, BuildError>
+    where
+        I: IntoIterator<Item = Option<T>>,
+    {
+        let values: Vec<Option<T>> = values.into_iter().collect();
+        if values.first().is_none_or(|root| root.is_none()) {
+            return match values.iter().position(Option::is_some) {
+                Some(index) => Err(BuildError::OrphanValue { index }),
+                None => Ok(None),
+            };
+        }
+
+        let mut children = vec![(None, None); values.len()];
+        let mut parents = VecDeque::from([0]);
+        let mut next = 1;
+        while next < values.len() {
+            let Some(parent) = parents.pop_front() else {
+                // No node is left to take children; only trailing holes may remain.
+                if let Some(offset) = values[next..].iter().position(Option::is_some) {
+                    return Err(BuildError::OrphanValue { index: next + offset });
+                }
+                break;
+            };
+            for slot in 0..2 {
+                if next == values.len() {
+                    break;
+                }
+                if values[next].is_some() {
+                    if slot == 0 {
+                        children[parent].0 = Some(next);
+                    } else {
+                        children[parent].1 = Some(next);
+                    }
+                    parents.push_back(next);
+                }
+                next += 1;
+            }
+        }
+        Ok(assemble(values, &children))
+    }
+}
+
+impl<T: Clone + Eq + Hash> Node<T> {
+    // Rebuilds the unique tree with the given preorder and inorder sequences.
+    // Values must be distinct, otherwise the tree is ambiguous.
+    pub fn from_preorder_inorder(preorder: &[T], inorder: &[T]) -> Result<Tree<T>, BuildError> {
+        if preorder.len() != inorder.len() {
+            return Err(BuildError::LengthMismatch {
+                preorder: preorder.len(),
+                inorder: inorder.len(),
+            });
+        }
+        let mut seen = HashSet::with_capacity(preorder.len());
+        if let Some(index) = preorder.iter().position(|value| !seen.insert(value)) {
+            return Err(BuildError::DuplicateValue { index });
+        }
+        if preorder.is_empty() {
+            return Ok(None);
+        }
+
+        // Walk the preorder with a stack of the current left spine: a value becomes the
+        // left child of the stack top until the top is the next inorder value, at which
+        // point the finished nodes are popped and the value hangs to the right of the last.
+        let mut children = vec![(None, None); preorder.len()];
+        let mut stack = vec![0];
+        let mut j = 0;
+        for i in 1..preorder.len() {
+            let top = *stack.last().expect("stack holds at least the current node");
+            if preorder[top] != inorder[j] {
+                children[top].0 = Some(i);
+            } else {
+                let mut last = top;
+                while let Some(&top) = stack.last() {
+                    if preorder[top] != inorder[j] {
+                        break;
+                    }
+                    last = top;
+                    stack.pop();
+                    j += 1;
+                }
+                children[last].1 = Some(i);
+            }
+            stack.push(i);
+        }
+
+        let tree = assemble(preorder.iter().cloned().map(Some).collect(), &children);
+        let root = tree.as_deref().expect("preorder is not empty");
+        if !root.iter_inorder().eq(inorder.iter()) {
+            return Err(BuildError::Inconsistent);
+        }
+        Ok(tree)
+    }
+}
+
+impl<T: Clone> Node<T> {
+    // The inverse of `from_level_order`, with trailing `None`s trimmed.
+    pub fn to_level_order(&self) -> Vec<Option<T>> {
+        let mut values = Vec::new();
+        let mut queue = VecDeque::from([Some(self)]);
+        while let Some(slot) = queue.pop_front() {
+            values.push(slot.map(|node| node.value.clone()));
+            if let Some(node) = slot {
+                queue.push_back(node.left.as_deref());
+                queue.push_back(node.right.as_deref());
+            }
+        }
+        while values.last().is_some_and(Option::is_none) {
+            values.pop();
+        }
+        values
+    }
+}
+
+impl<T: Clone + Into<Value>> Node<T> {
+    // Nested objects: {"value": 1, "left": {...}, "right": null}.
+    pub fn to_json(&self) -> Value {
+        fold_postorder(self, |node, left, right| {
+            json!({
+                "value": node.value.clone().into(),
+                "left": left.unwrap_or(Value::Null),
+                "right": right.unwrap_or(Value::Null),
+            })
+        })
+    }
+}
+
+impl<T: Display> Node<T> {
+    // A Graphviz digraph. A missing child next to a present one is drawn as an
+    // invisible point so left and right children keep their sides.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph tree {\n    node [shape=circle];\n");
+        let mut next_id = 1;
+        let mut stack = vec![(self, 0)];
+        while let Some((node, id)) = stack.pop() {
+            let label = node.value.to_string().replace('\\', "\\\\").replace('"', "\\\"");
+            let _ = writeln!(dot, "    n{} [label=\"{}\"];", id, label);
+            if node.left.is_none() && node.right.is_none() {
+                continue;
+            }
+            let mut pushed = Vec::with_capacity(2);
+            for child in [node.left.as_deref(), node.right.as_deref()] {
+                let child_id = next_id;
+                next_id += 1;
+                match child {
+                    Some(child) => {
+                        let _ = writeln!(dot, "    n{} -> n{};", id, child_id);
+                        pushed.push((child, child_id));
+                    }
+                    None => {
+                        let _ = writeln!(dot, "    n{} [shape=point, style=invis];", child_id);
+                        let _ = writeln!(dot, "    n{} -> n{} [style=invis];", id, child_id);
+                    }
+                }
+            }
+            // Pop the left child first so node ids follow preorder.
+            stack.extend(pushed.into_iter().rev());
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    // Draws the tree with box-drawing characters, left child above right child;
+    // a missing child next to a present one is shown as "∅".
+    //
+    // 1
+    // ├── 2
+    // └── 3
+    pub fn to_ascii(&self) -> String {
+        let mut out = String::new();
+        let mut stack: Vec<(Option<&Node<T>>, String, &str)> = vec![(Some(self), String::new(), "")];
+        while let Some((node, prefix, branch)) = stack.pop() {
+            let Some(node) = node else {
+                let _ = writeln!(out, "{}{}∅", prefix, branch);
+                continue;
+            };
+            let _ = writeln!(out, "{}{}{}", prefix, branch, node.value);
+            if node.left.is_none() && node.right.is_none() {
+                continue;
+            }
+            let child_prefix = format!(
+                "{}{}",
+                prefix,
+                match branch {
+                    "├── " => "│   ",
+                    "└── " => "    ",
+                    _ => "",
+                }
+            );
+            stack.push((node.right.as_deref(), child_prefix.clone(), "└── "));
+            stack.push((node.left.as_deref(), child_prefix, "├── "));
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_level_order() {
+        let tree = Node::from_level_order([Some(1), Some(2), Some(3), Some(4), Some(5), None, Some(6)]).unwrap();
+        let root = tree.as_deref().unwrap();
+        assert_eq!(root.iter_preorder().copied().collect::<Vec<_>>(), vec![1, 2, 4, 5, 3, 6]);
+
+        // Only present nodes take child slots.
+        let tree = Node::from_level_order([Some(1), None, Some(2), Some(3)]).unwrap();
+        let root = tree.as_deref().unwrap();
+        assert!(root.left.is_none());
+        assert_eq!(root.right.as_ref().unwrap().left.as_ref().unwrap().value, 3);
+
+        assert!(Node::<i32>::from_level_order([]).unwrap().is_none());
+        assert!(Node::<i32>::from_level_order([None, None]).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_from_level_order_rejects_orphans() {
+        assert_eq!(
+            Node::from_level_order([None, Some(1)]).unwrap_err(),
+            BuildError::OrphanValue { index: 1 }
+        );
+        // 1 has children None and None, so 7 has nowhere to go.
+        assert_eq!(
+            Node::from_level_order([Some(1), None, None, None, Some(7)]).unwrap_err(),
+            BuildError::OrphanValue { index: 4 }
+        );
+        assert!(Node::from_level_order([Some(1), None, None, None]).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_level_order_round_trip() {
+        let cases: Vec<Vec<Option<u32>>> = vec![
+            vec![Some(1)],
+            vec![Some(1), None, Some(2), Some(3)],
+            vec![Some(5), Some(4), Some(8), Some(11), None, Some(13), Some(4), Some(7), Some(2), None, None, None, Some(1)],
+        ];
+        for values in cases {
+            let tree = Node::from_level_order(values.clone()).unwrap().unwrap();
+            assert_eq!(tree.to_level_order(), values);
+        }
+    }
+
+    #[test]
+    fn test_from_preorder_inorder() {
+        let tree = Node::from_preorder_inorder(&[3, 9, 20, 15, 7], &[9, 3, 15, 20, 7]).unwrap().unwrap();
+        assert_eq!(tree.to_level_order(), vec![Some(3), Some(9), Some(20), None, None, Some(15), Some(7)]);
+
+        // A right-leaning chain and a left-leaning chain.
+        let right = Node::from_preorder_inorder(&[1, 2, 3], &[1, 2, 3]).unwrap().unwrap();
+        assert_eq!(right.to_level_order(), vec![Some(1), None, Some(2), None, Some(3)]);
+        let left = Node::from_preorder_inorder(&[1, 2, 3], &[3, 2, 1]).unwrap().unwrap();
+        assert_eq!(left.iter_postorder().copied().collect::<Vec<_>>(), vec![3, 2, 1]);
+
+        assert!(Node::<u8>::from_preorder_inorder(&[], &[]).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_from_preorder_inorder_errors() {
+        assert_eq!(
+            Node::from_preorder_inorder(&[1, 2], &[1]).unwrap_err(),
+            BuildError::LengthMismatch { preorder: 2, inorder: 1 }
+        );
+        assert_eq!(
+            Node::from_preorder_inorder(&[1, 2, 1], &[1, 2, 1]).unwrap_err(),
+            BuildError::DuplicateValue { index: 2 }
+        );
+        assert_eq!(Node::from_preorder_inorder(&[1, 2], &[1, 3]).unwrap_err(), BuildError::Inconsistent);
+        assert_eq!(Node::from_preorder_inorder(&[1, 2, 3], &[2, 3, 1]).map(|_| ()), Ok(()));
+        assert_eq!(Node::from_preorder_inorder(&[1, 2, 3], &[3, 1, 2]).unwrap_err(), BuildError::Inconsistent);
+    }
+
+    #[test]
+    fn test_preorder_inorder_round_trip_on_deep_tree() {
+        let n = 100_000u32;
+        // Preorder 0..n with inorder reversed describes a left chain.
+        let preorder: Vec<u32> = (0..n).collect();
+        let inorder: Vec<u32> = (0..n).rev().collect();
+        let tree = Node::from_preorder_inorder(&preorder, &inorder).unwrap().unwrap();
+        assert!(tree.iter_inorder().copied().eq(inorder.iter().copied()));
+        assert_eq!(tree.to_level_order().len(), 2 * n as usize - 2);
+    }
+
+    #[test]
+    fn test_to_json() {
+        let tree = Node::from_level_order([Some(1), None, Some(2)]).unwrap().unwrap();
+        assert_eq!(
+            tree.to_json(),
+            json!({
+                "value": 1,
+                "left": null,
+                "right": {"value": 2, "left": null, "right": null},
+            })
+        );
+        let words = Node::from_level_order([Some("a"), Some("b")]).unwrap().unwrap();
+        assert_eq!(words.to_json()["left"]["value"], "b");
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let tree = Node::from_level_order([Some(1), None, Some(2)]).unwrap().unwrap();
+        let dot = tree.to_dot();
+        assert!(dot.starts_with("digraph tree {\n"));
+        assert!(dot.contains("n0 [label=\"1\"];"));
+        assert!(dot.contains("n1 [shape=point, style=invis];"));
+        assert!(dot.contains("n0 -> n2;"));
+        assert!(dot.contains("n2 [label=\"2\"];"));
+        assert!(dot.ends_with("}\n"));
+
+        let quoted = Node::new("say \"hi\"");
+        assert!(quoted.to_dot().contains(r#"[label="say \"hi\""]"#));
+    }
+
+    #[test]
+    fn test_to_ascii() {
+        let tree = Node::from_level_order([Some(1), Some(2), Some(3), Some(4), Some(5), None, Some(6)]).unwrap().unwrap();
+        let expected = "\
+1
+├── 2
+│   ├── 4
+│   └── 5
+└── 3
+    ├── ∅
+    └── 6
+";
+        assert_eq!(tree.to_ascii(), expected);
+    }
+}
@@ -0,0 +1,239 @@
+// 测试特性：非递归遍历、显式栈与队列、迭代器适配
+// 语法要点：impl Iterator for 结构体、Vec 作栈、VecDeque 作队列
+// 功能：为 Node<T> 提供前序、中序、后序、层序四种返回 &T 的迭代器，
+//       以及消耗树、只用 O(1) 额外空间的中序迭代器(旋转成右链，代替 Morris 遍历)，
+//       深度很大的树也不会栈溢出
+
+use std::collections::VecDeque;
+
+use super::Node;
+
+// Root, left subtree, right subtree.
+pub struct PreOrder<'a, T> {
+    stack: Vec<&'a Node<T>>,
+}
+
+impl<'a, T> PreOrder<'a, T> {
+    pub fn new(root: Option<&'a Node<T>>) -> Self {
+        PreOrder {
+            stack: root.into_iter().collect(),
+        }
+    }
+}
+
+impl<'a, T> Iterator for PreOrder<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        let node = self.stack.pop()?;
+        // Push right first so the left subtree is visited first.
+        self.stack.extend(node.right.as_deref());
+        self.stack.extend(node.left.as_deref());
+        Some(&node.value)
+    }
+}
+
+// Left subtree, root, right subtree.
+pub struct InOrder<'a, T> {
+    stack: Vec<&'a Node<T>>,
+}
+
+impl<'a, T> InOrder<'a, T> {
+    pub fn new(root: Option<&'a Node<T>>) -> Self {
+        let mut iter = InOrder { stack: Vec::new() };
+        iter.push_left_spine(root);
+        iter
+    }
+
+    fn push_left_spine(&mut self, mut node: Option<&'a Node<T>>) {
+        while let Some(n) = node {
+            self.stack.push(n);
+            node = n.left.as_deref();
+        }
+    }
+}
+
+impl<'a, T> Iterator for InOrder<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        let node = self.stack.pop()?;
+        self.push_left_spine(node.right.as_deref());
+        Some(&node.value)
+    }
+}
+
+// Left subtree, right subtree, root.
+pub struct PostOrder<'a, T> {
+    // The flag records whether the node's children have already been pushed.
+    stack: Vec<(&'a Node<T>, bool)>,
+}
+
+impl<'a, T> PostOrder<'a, T> {
+    pub fn new(root: Option<&'a Node<T>>) -> Self {
+        PostOrder {
+            stack: root.map(|node| (node, false)).into_iter().collect(),
+        }
+    }
+}
+
+impl<'a, T> Iterator for PostOrder<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        loop {
+            let (node, expanded) = self.stack.pop()?;
+            if expanded {
+                return Some(&node.value);
+            }
+            self.stack.push((node, true));
+            self.stack.extend(node.right.as_deref().map(|n| (n, false)));
+            self.stack.extend(node.left.as_deref().map(|n| (n, false)));
+        }
+    }
+}
+
+// Breadth first, top to bottom and left to right within a level.
+pub struct LevelOrder<'a, T> {
+    queue: VecDeque<&'a Node<T>>,
+}
+
+impl<'a, T> LevelOrder<'a, T> {
+    pub fn new(root: Option<&'a Node<T>>) -> Self {
+        LevelOrder {
+            queue: root.into_iter().collect(),
+        }
+    }
+}
+
+impl<'a, T> Iterator for LevelOrder<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        let node = self.queue.pop_front()?;
+        self.queue.extend(node.left.as_deref());
+        self.queue.extend(node.right.as_deref());
+        Some(&node.value)
+    }
+}
+
+// Consuming in-order traversal in O(1) extra space, the owning counterpart of Morris
+// traversal. Morris threads the rightmost node of each left subtree back to its
+// successor, which with owning `Box` links would mean two Boxes owning one node.
+// Owning the tree allows something sound instead: while the current root has a left
+// child, rotate right so that child becomes the root; once there is no left child the
+// root is the smallest remaining value and its right subtree is what is left. Every
+// rotation moves one node onto the right spine for good, so there are fewer than n
+// rotations in total and the walk needs no stack at all.
+pub struct IntoInOrder<T> {
+    root: Option<Box<Node<T>>>,
+}
+
+impl<T> IntoInOrder<T> {
+    pub fn new(root: Option<Box<Node<T>>>) -> Self {
+        IntoInOrder { root }
+    }
+}
+
+impl<T> Iterator for IntoInOrder<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        let mut node = self.root.take()?;
+        //     node        left
+        //     /  \        /  \
+        //   left  c  ==> a   node
+        //   /  \             /  \
+        //  a    b           b    c
+        while let Some(mut left) = node.left.take() {
+            node.left = left.right.take();
+            left.right = Some(node);
+            node = left;
+        }
+        self.root = node.right.take();
+        Some(node.into_value())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{left_chain, Node, TraversalType};
+
+    //      1
+    //     / \
+    //    2   3
+    //   / \   \
+    //  4   5   6
+    fn sample() -> Node<u32> {
+        let mut root = Node::new(1);
+        let mut two = Node::new(2);
+        let mut three = Node::new(3);
+        two.left = Some(Box::new(Node::new(4)));
+        two.right = Some(Box::new(Node::new(5)));
+        three.right = Some(Box::new(Node::new(6)));
+        root.left = Some(Box::new(two));
+        root.right = Some(Box::new(three));
+        root
+    }
+
+    #[test]
+    fn test_iterators() {
+        let tree = sample();
+        assert_eq!(tree.iter_preorder().copied().collect::<Vec<_>>(), vec![1, 2, 4, 5, 3, 6]);
+        assert_eq!(tree.iter_inorder().copied().collect::<Vec<_>>(), vec![4, 2, 5, 1, 3, 6]);
+        assert_eq!(tree.iter_postorder().copied().collect::<Vec<_>>(), vec![4, 5, 2, 6, 3, 1]);
+        assert_eq!(tree.iter_level_order().copied().collect::<Vec<_>>(), vec![1, 2, 3, 4, 5, 6]);
+
+        let leaf = Node::new('x');
+        assert_eq!(leaf.iter_postorder().collect::<Vec<_>>(), vec![&'x']);
+    }
+
+    #[test]
+    fn test_visit() {
+        let tree = sample();
+        for (order, expected) in [
+            (TraversalType::PreOrder, vec![1, 2, 4, 5, 3, 6]),
+            (TraversalType::InOrder, vec![4, 2, 5, 1, 3, 6]),
+            (TraversalType::PostOrder, vec![4, 5, 2, 6, 3, 1]),
+            (TraversalType::LevelOrder, vec![1, 2, 3, 4, 5, 6]),
+        ] {
+            let mut seen = Vec::new();
+            tree.visit(order, |&v| seen.push(v));
+            assert_eq!(seen, expected, "{:?}", order);
+        }
+    }
+
+    #[test]
+    fn test_into_inorder() {
+        let expected: Vec<u32> = sample().iter_inorder().copied().collect();
+        assert_eq!(sample().into_inorder().collect::<Vec<_>>(), expected);
+
+        // A zig-zag shape needs rotations below the right spine as well.
+        let zigzag = Node::from_level_order([Some(5), Some(1), Some(9), None, Some(3), Some(7), None, Some(2), Some(4), None, Some(8)])
+            .unwrap()
+            .unwrap();
+        let expected: Vec<i32> = zigzag.iter_inorder().copied().collect();
+        assert_eq!(zigzag.into_inorder().collect::<Vec<_>>(), expected);
+
+        // Values that own heap memory are moved out once and the rest are dropped with the iterator.
+        let mut root = Node::new("b".to_string());
+        root.left = Some(Box::new(Node::new("a".to_string())));
+        root.right = Some(Box::new(Node::new("c".to_string())));
+        let mut iter = root.into_inorder();
+        assert_eq!(iter.next().as_deref(), Some("a"));
+        drop(iter);
+    }
+
+    #[test]
+    fn test_deep_tree_does_not_overflow() {
+        let n = 200_000;
+        let tree = left_chain(n);
+        assert_eq!(tree.iter_preorder().count(), n as usize);
+        assert_eq!(tree.iter_inorder().next(), Some(&0));
+        assert_eq!(tree.iter_postorder().next(), Some(&0));
+        assert_eq!(tree.iter_level_order().last(), Some(&0));
+        assert!(tree.into_inorder().eq(0..n));
+        // Dropping the chain must not recurse either.
+        drop(left_chain(n));
+    }
+}
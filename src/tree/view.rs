@@ -0,0 +1,125 @@
+// 测试特性：用 trait 抽象不同的二叉树节点、泛型算法复用
+// 语法要点：trait 关联类型 Key、Option<&Self> 返回子节点、泛型迭代器结构体
+// 功能：为普通二叉树、二叉搜索树、AVL 树和红黑树的节点提供统一的只读/可变视图，
+//       查找、最值、树高和中序遍历只需实现一次
+
+use std::cmp::Ordering;
+
+use super::Node;
+
+// The shape of a binary tree node.
+pub(crate) trait BinaryNode {
+    fn left(&self) -> Option<&Self>;
+    fn right(&self) -> Option<&Self>;
+}
+
+impl<T> BinaryNode for Node<T> {
+    fn left(&self) -> Option<&Self> {
+        self.left.as_deref()
+    }
+
+    fn right(&self) -> Option<&Self> {
+        self.right.as_deref()
+    }
+}
+
+// A node of a binary search tree keyed by `Key`.
+pub(crate) trait EntryNode: BinaryNode {
+    type Key;
+
+    fn key(&self) -> &Self::Key;
+    fn left_mut(&mut self) -> Option<&mut Self>;
+    fn right_mut(&mut self) -> Option<&mut Self>;
+}
+
+// Number of nodes on the longest root-to-leaf path, counted level by level
+// because an unbalanced tree may be too deep to recurse over.
+pub(crate) fn height<N: BinaryNode>(root: Option<&N>) -> usize {
+    let mut level: Vec<&N> = root.into_iter().collect();
+    let mut height = 0;
+    while !level.is_empty() {
+        height += 1;
+        level = level
+            .iter()
+            .flat_map(|node| node.left().into_iter().chain(node.right()))
+            .collect();
+    }
+    height
+}
+
+pub(crate) fn leftmost<N: BinaryNode>(root: Option<&N>) -> Option<&N> {
+    let mut node = root?;
+    while let Some(left) = node.left() {
+        node = left;
+    }
+    Some(node)
+}
+
+pub(crate) fn rightmost<N: BinaryNode>(root: Option<&N>) -> Option<&N> {
+    let mut node = root?;
+    while let Some(right) = node.right() {
+        node = right;
+    }
+    Some(node)
+}
+
+pub(crate) fn find<'a, N>(mut current: Option<&'a N>, key: &N::Key) -> Option<&'a N>
+where
+    N: EntryNode,
+    N::Key: Ord,
+{
+    while let Some(node) = current {
+        current = match key.cmp(node.key()) {
+            Ordering::Less => node.left(),
+            Ordering::Greater => node.right(),
+            Ordering::Equal => return Some(node),
+        };
+    }
+    None
+}
+
+pub(crate) fn find_mut<'a, N>(mut current: Option<&'a mut N>, key: &N::Key) -> Option<&'a mut N>
+where
+    N: EntryNode,
+    N::Key: Ord,
+{
+    while let Some(node) = current {
+        current = match key.cmp(node.key()) {
+            Ordering::Less => node.left_mut(),
+            Ordering::Greater => node.right_mut(),
+            Ordering::Equal => return Some(node),
+        };
+    }
+    None
+}
+
+// In-order walk over the nodes. The stack holds the path of nodes whose left subtree
+// has been visited (or is being visited) but which themselves have not been yielded yet.
+pub(crate) struct InOrderNodes<'a, N> {
+    stack: Vec<&'a N>,
+}
+
+impl<'a, N: BinaryNode> InOrderNodes<'a, N> {
+    pub(crate) fn new(root: Option<&'a N>) -> Self {
+        let mut iter = InOrderNodes { stack: Vec::new() };
+        iter.push_left_spine(root);
+        iter
+    }
+
+    fn push_left_spine(&mut self, mut node: Option<&'a N>) {
+        while let Some(n) = node {
+            self.stack.push(n);
+            node = n.left();
+        }
+    }
+}
+
+impl<'a, N: BinaryNode> Iterator for InOrderNodes<'a, N> {
+    type Item = &'a N;
+
+    fn next(&mut self) -> Option<&'a N> {
+        let node = self.stack.pop()?;
+        self.push_left_spine(node.right());
+        Some(node)
+    }
+}